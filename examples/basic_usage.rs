@@ -163,6 +163,7 @@ async fn with_attachment(webhook_url: &str) -> Result<(), Box<dyn std::error::Er
     let attachment = Attachment {
         path: PathBuf::from("falco.png"),
         description: Some("Chess game file".to_string()),
+        ..Default::default()
     };
 
     let response = webhook
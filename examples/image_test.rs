@@ -29,6 +29,7 @@ async fn simple_message_with_attachment(
     let attachment = Attachment {
         path: PathBuf::from("examples/falco.png"),
         description: Some("Falco image".to_string()),
+        ..Default::default()
     };
 
     let response = webhook
@@ -64,6 +65,7 @@ async fn embed_message_with_attachment(
     let attachment = Attachment {
         path: PathBuf::from("examples/falco.png"),
         description: Some("Falco image".to_string()),
+        ..Default::default()
     };
 
     let response = webhook
@@ -87,6 +89,7 @@ async fn embed_message_with_inline_image(
     let attachment = Attachment {
         path: PathBuf::from("examples/falco.png"),
         description: Some("Falco image".to_string()),
+        ..Default::default()
     };
 
     let embed = Embed {
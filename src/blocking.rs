@@ -0,0 +1,39 @@
+use crate::error::Result;
+use crate::models::{Attachment, MessageBuilder, WebhookResponse};
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// Synchronous wrapper around [`crate::Webhook`]. Each call blocks on a private
+/// current-thread runtime, so it must not be used from inside an async context.
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    inner: crate::Webhook,
+    runtime: Arc<Runtime>,
+}
+
+impl Webhook {
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        Self::from_async(crate::Webhook::new(url)?)
+    }
+
+    pub fn from_async(inner: crate::Webhook) -> Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    pub fn send(&self, message: MessageBuilder) -> Result<WebhookResponse> {
+        self.runtime.block_on(self.inner.send(message))
+    }
+
+    pub fn send_with_attachments(&self, message: MessageBuilder, attachments: Vec<Attachment>) -> Result<WebhookResponse> {
+        self.runtime
+            .block_on(self.inner.send_with_attachments(message, attachments))
+    }
+
+    pub fn execute(&self, wait: bool) -> Result<WebhookResponse> {
+        self.runtime.block_on(self.inner.execute(wait))
+    }
+}
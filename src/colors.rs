@@ -0,0 +1,102 @@
+use crate::error::{Result, WebhookError};
+
+pub const BLURPLE: u32 = 0x5865F2;
+pub const GREEN: u32 = 0x57F287;
+pub const YELLOW: u32 = 0xFEE75C;
+pub const FUCHSIA: u32 = 0xEB459E;
+pub const RED: u32 = 0xED4245;
+pub const WHITE: u32 = 0xFFFFFF;
+pub const BLACK: u32 = 0x23272A;
+
+/// An embed color, convertible into the raw `u32` Discord expects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Color(pub u32);
+
+impl Color {
+    pub const BLURPLE: Color = Color(BLURPLE);
+    pub const GREEN: Color = Color(GREEN);
+    pub const YELLOW: Color = Color(YELLOW);
+    pub const FUCHSIA: Color = Color(FUCHSIA);
+    pub const RED: Color = Color(RED);
+    pub const WHITE: Color = Color(WHITE);
+    pub const BLACK: Color = Color(BLACK);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color((r as u32) << 16 | (g as u32) << 8 | b as u32)
+    }
+
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<Color> for u32 {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+/// Looks up a CSS color name (case-insensitive, e.g. `"orange"` or `"SteelBlue"`).
+/// Returns `None` for names it doesn't know so callers can fall back.
+pub fn named_color(name: &str) -> Option<u32> {
+    let color = match name.trim().to_ascii_lowercase().as_str() {
+        "black" => 0x000000,
+        "white" => 0xFFFFFF,
+        "red" => 0xFF0000,
+        "green" => 0x008000,
+        "blue" => 0x0000FF,
+        "yellow" => 0xFFFF00,
+        "orange" => 0xFFA500,
+        "purple" => 0x800080,
+        "pink" => 0xFFC0CB,
+        "gray" | "grey" => 0x808080,
+        "silver" => 0xC0C0C0,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "lime" => 0x00FF00,
+        "aqua" | "cyan" => 0x00FFFF,
+        "teal" => 0x008080,
+        "navy" => 0x000080,
+        "fuchsia" | "magenta" => 0xFF00FF,
+        "brown" => 0xA52A2A,
+        "gold" => 0xFFD700,
+        "indigo" => 0x4B0082,
+        "violet" => 0xEE82EE,
+        "crimson" => 0xDC143C,
+        "coral" => 0xFF7F50,
+        "salmon" => 0xFA8072,
+        "tomato" => 0xFF6347,
+        "khaki" => 0xF0E68C,
+        "turquoise" => 0x40E0D0,
+        "orchid" => 0xDA70D6,
+        "plum" => 0xDDA0DD,
+        "lavender" => 0xE6E6FA,
+        "beige" => 0xF5F5DC,
+        "tan" => 0xD2B48C,
+        "chocolate" => 0xD2691E,
+        "skyblue" => 0x87CEEB,
+        "steelblue" => 0x4682B4,
+        "slategray" | "slategrey" => 0x708090,
+        "lightgray" | "lightgrey" => 0xD3D3D3,
+        "darkgray" | "darkgrey" => 0xA9A9A9,
+        "darkred" => 0x8B0000,
+        "darkgreen" => 0x006400,
+        "darkblue" => 0x00008B,
+        "darkorange" => 0xFF8C00,
+        _ => return None,
+    };
+    Some(color)
+}
+
+pub(crate) fn parse_hex(input: &str) -> Result<u32> {
+    let hex = input.trim().trim_start_matches('#');
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return Err(WebhookError::InvalidColor(input.to_string())),
+    };
+    if !expanded.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(WebhookError::InvalidColor(input.to_string()));
+    }
+    u32::from_str_radix(&expanded, 16).map_err(|_| WebhookError::InvalidColor(input.to_string()))
+}
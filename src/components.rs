@@ -0,0 +1,246 @@
+use crate::error::{Result, WebhookError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ButtonStyle {
+    #[default]
+    Primary = 1,
+    Secondary = 2,
+    Success = 3,
+    Danger = 4,
+    Link = 5,
+}
+
+impl Serialize for ButtonStyle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for ButtonStyle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(Self::Primary),
+            2 => Ok(Self::Secondary),
+            3 => Ok(Self::Success),
+            4 => Ok(Self::Danger),
+            5 => Ok(Self::Link),
+            other => Err(serde::de::Error::custom(format!("unknown button style {}", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ButtonEmoji {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(skip_serializing_if = "crate::models::is_false")]
+    #[serde(default)]
+    pub animated: bool,
+}
+
+impl ButtonEmoji {
+    pub fn unicode(emoji: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: emoji.into(),
+            animated: false,
+        }
+    }
+
+    pub fn custom(id: impl Into<String>, name: impl Into<String>, animated: bool) -> Result<Self> {
+        let emoji = Self {
+            id: Some(id.into()),
+            name: name.into(),
+            animated,
+        };
+        emoji.validate()?;
+        Ok(emoji)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(id) = &self.id
+            && (id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return Err(WebhookError::InvalidEmoji(id.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for ButtonEmoji {
+    fn from(emoji: &str) -> Self {
+        Self::unicode(emoji)
+    }
+}
+
+impl From<String> for ButtonEmoji {
+    fn from(emoji: String) -> Self {
+        Self::unicode(emoji)
+    }
+}
+
+impl From<char> for ButtonEmoji {
+    fn from(emoji: char) -> Self {
+        Self::unicode(emoji.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Button {
+    pub style: ButtonStyle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<ButtonEmoji>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_id: Option<String>,
+    #[serde(skip_serializing_if = "crate::models::is_false")]
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+impl Button {
+    pub fn new(style: ButtonStyle) -> Self {
+        Self {
+            style,
+            ..Default::default()
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn emoji(mut self, emoji: impl Into<ButtonEmoji>) -> Self {
+        self.emoji = Some(emoji.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn custom_id(mut self, custom_id: impl Into<String>) -> Self {
+        self.custom_id = Some(custom_id.into());
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(emoji) = &self.emoji {
+            emoji.validate()?;
+        }
+        match self.style {
+            ButtonStyle::Link if self.url.is_none() || self.custom_id.is_some() => Err(WebhookError::InvalidComponent(
+                "link buttons need a url and no custom_id".to_string(),
+            )),
+            ButtonStyle::Link => Ok(()),
+            _ if self.custom_id.is_none() || self.url.is_some() => Err(WebhookError::InvalidComponent(
+                "non-link buttons need a custom_id and no url".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Component {
+    ActionRow { components: Vec<Component> },
+    Button(Button),
+}
+
+impl Component {
+    const ACTION_ROW: u8 = 1;
+    const BUTTON: u8 = 2;
+
+    pub fn action_row(components: impl IntoIterator<Item = impl Into<Component>>) -> Self {
+        Self::ActionRow {
+            components: components.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::ActionRow { components } => components.iter().try_for_each(|component| match component {
+                Self::ActionRow { .. } => Err(WebhookError::InvalidComponent(
+                    "action rows cannot be nested".to_string(),
+                )),
+                Self::Button(button) => button.validate(),
+            }),
+            Self::Button(button) => button.validate(),
+        }
+    }
+}
+
+impl From<Button> for Component {
+    fn from(button: Button) -> Self {
+        Self::Button(button)
+    }
+}
+
+#[derive(Serialize)]
+struct Tagged<'a, T> {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(flatten)]
+    inner: &'a T,
+}
+
+#[derive(Serialize)]
+struct ActionRowBody<'a> {
+    components: &'a [Component],
+}
+
+impl Serialize for Component {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::ActionRow { components } => Tagged {
+                kind: Self::ACTION_ROW,
+                inner: &ActionRowBody { components },
+            }
+            .serialize(serializer),
+            Self::Button(button) => Tagged {
+                kind: Self::BUTTON,
+                inner: button,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Component {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        struct ActionRowOwned {
+            #[serde(default)]
+            components: Vec<Component>,
+        }
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let kind = value.get("type").and_then(serde_json::Value::as_u64);
+        match kind {
+            Some(kind) if kind == u64::from(Self::ACTION_ROW) => {
+                let row: ActionRowOwned = serde_json::from_value(value).map_err(D::Error::custom)?;
+                Ok(Self::ActionRow {
+                    components: row.components,
+                })
+            }
+            Some(kind) if kind == u64::from(Self::BUTTON) => {
+                serde_json::from_value(value).map(Self::Button).map_err(D::Error::custom)
+            }
+            other => Err(D::Error::custom(format!("unsupported component type {:?}", other))),
+        }
+    }
+}
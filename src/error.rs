@@ -1,3 +1,4 @@
+use serde::Deserialize;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,7 +7,11 @@ pub enum WebhookError {
     Http(#[from] hyper::Error),
 
     #[error("HTTP status error: {status}")]
-    Status { status: hyper::StatusCode, body: String },
+    Status {
+        status: hyper::StatusCode,
+        body: String,
+        retry_after: Option<std::time::Duration>,
+    },
 
     #[error("JSON serialization error: {0}")]
     Serialize(#[from] serde_json::Error),
@@ -15,19 +20,99 @@ pub enum WebhookError {
     Io(#[from] std::io::Error),
 
     #[error("TLS error: {0}")]
+    #[cfg(feature = "native-tls")]
     Tls(#[from] native_tls::Error),
 
     #[error("Tokio TLS handshake error: {0}")]
     TokioTls(String),
 
+    #[error("Request timed out after {0} seconds")]
+    Timeout(u64),
+
+    #[error("Cannot send an empty message")]
+    EmptyMessage,
+
+    #[error("Request was cancelled")]
+    Cancelled,
+
+    #[error("Attachments too large: {size} bytes in total (max {max})")]
+    AttachmentTooLarge { size: u64, max: u64 },
+
+    #[error("Invalid color: {0}")]
+    InvalidColor(String),
+
+    #[error("Invalid username: {0}")]
+    InvalidUsername(String),
+
+    #[error("Invalid embed URL: {0}")]
+    InvalidEmbedUrl(String),
+
+    #[error("Invalid component: {0}")]
+    InvalidComponent(String),
+
+    #[error("Invalid custom emoji id: {0}")]
+    InvalidEmoji(String),
+
     #[error("Invalid webhook URL")]
     InvalidUrl,
 
+    #[error("Invalid root certificate: {0}")]
+    InvalidCertificate(String),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
     #[error("Request error: {0}")]
     Request(String),
 
-    #[error("Content too long: {0} characters (max 6000)")]
+    #[error("Content too long: {0} characters (max 2000)")]
     ContentTooLong(usize),
+
+    #[error("Embeds too long: {0} characters in total (max 6000)")]
+    EmbedTooLong(usize),
+
+    #[error("{field} too long: {len} characters (max {max})")]
+    FieldTooLong { field: &'static str, len: usize, max: usize },
+
+    #[error("Embed {embed_index} has too many fields: {count} (max 25)")]
+    TooManyFields { embed_index: usize, count: usize },
+
+    #[error("Too many attachments: {0} (max 10)")]
+    TooManyAttachments(usize),
+
+    #[error("Failed to read attachment {}: {source}", path.display())]
+    AttachmentIo {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("No attachment named {0} for its attachment:// reference")]
+    MissingAttachment(String),
+}
+
+impl WebhookError {
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            WebhookError::Status { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    pub fn api_error(&self) -> Option<DiscordApiError> {
+        match self {
+            WebhookError::Status { body, .. } => serde_json::from_str(body).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DiscordApiError {
+    pub message: String,
+    pub code: u32,
+    #[serde(default)]
+    pub errors: Option<serde_json::Value>,
 }
 
 pub type Result<T> = std::result::Result<T, WebhookError>;
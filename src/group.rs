@@ -0,0 +1,40 @@
+use crate::error::Result;
+use crate::models::{MessageBuilder, WebhookResponse};
+use crate::webhook::Webhook;
+
+/// Sends the same message to several webhooks at once.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookGroup {
+    pub webhooks: Vec<Webhook>,
+}
+
+impl WebhookGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, webhook: Webhook) -> Self {
+        self.webhooks.push(webhook);
+        self
+    }
+
+    /// Sends a copy of `message` to every webhook concurrently. Results are in the order
+    /// the webhooks were added, and a failure only affects its own slot.
+    pub async fn send(&self, message: MessageBuilder) -> Vec<Result<WebhookResponse>> {
+        futures_util::future::join_all(self.webhooks.iter().map(|webhook| webhook.send(message.clone()))).await
+    }
+}
+
+impl From<Vec<Webhook>> for WebhookGroup {
+    fn from(webhooks: Vec<Webhook>) -> Self {
+        Self { webhooks }
+    }
+}
+
+impl FromIterator<Webhook> for WebhookGroup {
+    fn from_iter<I: IntoIterator<Item = Webhook>>(iter: I) -> Self {
+        Self {
+            webhooks: iter.into_iter().collect(),
+        }
+    }
+}
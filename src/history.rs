@@ -0,0 +1,41 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone)]
+pub struct SendRecord {
+    pub timestamp: SystemTime,
+    pub status: Option<u16>,
+    pub latency: Duration,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct History {
+    capacity: usize,
+    records: Arc<Mutex<VecDeque<SendRecord>>>,
+}
+
+impl History {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    pub(crate) fn push(&self, record: SendRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<SendRecord> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+use serde_json::ser::Formatter;
+use std::io;
+
+struct AsciiFormatter;
+
+impl Formatter for AsciiFormatter {
+    fn write_string_fragment<W: ?Sized + io::Write>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()> {
+        for c in fragment.chars() {
+            if c.is_ascii() {
+                writer.write_all(&[c as u8])?;
+            } else {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    write!(writer, "\\u{:04x}", unit)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn to_vec<T: Serialize + ?Sized>(value: &T, ascii_only: bool) -> crate::error::Result<Vec<u8>> {
+    if !ascii_only {
+        return Ok(serde_json::to_vec(value)?);
+    }
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, AsciiFormatter);
+    value.serialize(&mut serializer)?;
+    Ok(out)
+}
@@ -0,0 +1,46 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Paces requests to a fixed rate. Clones share the same schedule, so one limiter can
+/// be handed to every clone of a `Webhook` (or to several webhooks).
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    /// Allows at most `requests` requests per second; 0 is treated as 1.
+    pub fn per_second(requests: u32) -> Self {
+        Self::with_interval(Duration::from_secs(1) / requests.max(1))
+    }
+
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_slot: Arc::default(),
+        }
+    }
+
+    /// Waits for this caller's turn.
+    pub(crate) async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let now = Instant::now();
+            let slot = next_slot.map_or(now, |next| next.max(now));
+            *next_slot = Some(slot + self.interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Holds every caller back for `delay`, e.g. after a 429 with a retry-after.
+    pub(crate) fn defer(&self, delay: Duration) {
+        let mut next_slot = self.next_slot.lock().unwrap();
+        let resume = Instant::now() + delay;
+        if next_slot.is_none_or(|next| next < resume) {
+            *next_slot = Some(resume);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::error::WebhookError;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricEvent {
+    RequestStarted,
+    RequestCompleted { status: u16, latency: Duration },
+    RetryScheduled { attempt: u32, delay: Duration },
+    RateLimited { retry_after: Option<Duration> },
+}
+
+#[derive(Clone)]
+pub(crate) struct MetricsHook(Arc<dyn Fn(MetricEvent) + Send + Sync>);
+
+impl MetricsHook {
+    pub(crate) fn new(hook: impl Fn(MetricEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    pub(crate) fn emit(&self, event: MetricEvent) {
+        (self.0)(event)
+    }
+}
+
+impl fmt::Debug for MetricsHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetricsHook")
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ErrorHook(Arc<dyn Fn(&WebhookError) + Send + Sync>);
+
+impl ErrorHook {
+    pub(crate) fn new(hook: impl Fn(&WebhookError) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    pub(crate) fn report(&self, err: &WebhookError) {
+        (self.0)(err)
+    }
+}
+
+impl fmt::Debug for ErrorHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorHook")
+    }
+}
@@ -0,0 +1,81 @@
+use crate::models::WebhookResponse;
+use crate::transport::{Transport, TransportFuture};
+use hyper::body::Bytes;
+use hyper::http::{HeaderMap, Method, Uri};
+use hyper::{Request, Response};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl RecordedRequest {
+    pub fn json(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+/// A [`Transport`] that never touches the network. Every request is recorded and
+/// answered with the next queued response, or an empty 204 once the queue runs dry.
+/// The `headers` and `diagnostics` of a queued response are sent back as response headers.
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<VecDeque<WebhookResponse>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_responses(responses: impl IntoIterator<Item = WebhookResponse>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter().collect()),
+            requests: Mutex::default(),
+        }
+    }
+
+    pub fn push_response(&self, response: WebhookResponse) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn last_request(&self) -> Option<RecordedRequest> {
+        self.requests.lock().unwrap().last().cloned()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: Request<Bytes>) -> TransportFuture<'_> {
+        let (parts, body) = request.into_parts();
+        self.requests.lock().unwrap().push(RecordedRequest {
+            method: parts.method,
+            uri: parts.uri,
+            headers: parts.headers,
+            body,
+        });
+
+        let canned = self.responses.lock().unwrap().pop_front().unwrap_or(WebhookResponse {
+            status_code: 204,
+            ..Default::default()
+        });
+        Box::pin(async move {
+            let mut builder = Response::builder().status(canned.status_code);
+            for (name, value) in canned.headers.iter().chain(&canned.diagnostics) {
+                builder = builder.header(name.as_str(), value.as_str());
+            }
+            builder
+                .body(Bytes::from(canned.body))
+                .map_err(|e| crate::error::WebhookError::Request(e.to_string()))
+        })
+    }
+}
@@ -1,11 +1,153 @@
-use serde::Serialize;
+use crate::components::Component;
+use crate::poll::Poll;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
-fn is_false(b: &bool) -> bool {
+pub(crate) const MAX_CONTENT_LENGTH: usize = 2000;
+pub(crate) const MAX_EMBEDS: usize = 10;
+pub(crate) const MAX_ATTACHMENTS: usize = 10;
+pub(crate) const MAX_EMBED_FIELDS: usize = 25;
+pub(crate) const MAX_EMBED_TOTAL_LENGTH: usize = 6000;
+pub(crate) const MAX_TITLE_LENGTH: usize = 256;
+pub(crate) const MAX_DESCRIPTION_LENGTH: usize = 4096;
+pub(crate) const MAX_FIELD_NAME_LENGTH: usize = 256;
+pub(crate) const MAX_FIELD_VALUE_LENGTH: usize = 1024;
+pub(crate) const MAX_FOOTER_TEXT_LENGTH: usize = 2048;
+pub(crate) const MAX_AUTHOR_NAME_LENGTH: usize = 256;
+pub(crate) const MAX_USERNAME_LENGTH: usize = 80;
+
+/// Splits `content` into chunks of at most `max` characters, breaking after newlines
+/// where possible and hard-splitting lines that are longer than `max`. Whitespace that
+/// would be left over as a chunk of its own at a boundary is dropped, since Discord
+/// trims it from message content anyway.
+pub(crate) fn split_content(content: &str, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut push = |chunk: String| {
+        if !chunk.trim().is_empty() {
+            chunks.push(chunk);
+        }
+    };
+    let mut current = String::new();
+    let mut current_len = 0;
+    for line in content.split_inclusive('\n') {
+        let line_len = line.chars().count();
+        if current_len + line_len > max && !current.is_empty() {
+            push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if line_len <= max {
+            current.push_str(line);
+            current_len += line_len;
+            continue;
+        }
+        let mut chars = line.chars().peekable();
+        while chars.peek().is_some() {
+            let piece: String = chars.by_ref().take(max).collect();
+            if chars.peek().is_some() {
+                push(piece);
+            } else {
+                current_len = piece.chars().count();
+                current = piece;
+            }
+        }
+    }
+    push(current);
+    chunks
+}
+
+fn truncate_chars(s: &str, max: usize) -> String {
+    s.chars().take(max).collect()
+}
+
+pub(crate) fn check_length(field: &'static str, value: &str, max: usize) -> crate::error::Result<()> {
+    let len = value.chars().count();
+    if len > max {
+        return Err(crate::error::WebhookError::FieldTooLong { field, len, max });
+    }
+    Ok(())
+}
+
+fn validate_username(username: &str) -> crate::error::Result<()> {
+    let len = username.chars().count();
+    if len == 0 || len > MAX_USERNAME_LENGTH {
+        return Err(crate::error::WebhookError::InvalidUsername(format!(
+            "must be 1-{} characters, got {}",
+            MAX_USERNAME_LENGTH, len
+        )));
+    }
+    let lowered = username.to_lowercase();
+    if let Some(word) = ["clyde", "discord"].into_iter().find(|word| lowered.contains(word)) {
+        return Err(crate::error::WebhookError::InvalidUsername(format!(
+            "must not contain \"{}\"",
+            word
+        )));
+    }
+    Ok(())
+}
+
+fn is_rfc3339(s: &str) -> bool {
+    let b = s.as_bytes();
+    let num = |range: std::ops::Range<usize>| -> Option<u32> {
+        let digits = b.get(range)?;
+        digits
+            .iter()
+            .all(u8::is_ascii_digit)
+            .then(|| digits.iter().fold(0, |acc, d| acc * 10 + u32::from(d - b'0')))
+    };
+    let sep = |idx: usize, allowed: &[u8]| b.get(idx).is_some_and(|c| allowed.contains(c));
+
+    let (Some(year), Some(month), Some(day)) = (num(0..4), num(5..7), num(8..10)) else {
+        return false;
+    };
+    let (Some(hour), Some(minute), Some(second)) = (num(11..13), num(14..16), num(17..19)) else {
+        return false;
+    };
+    if !(sep(4, b"-") && sep(7, b"-") && sep(10, b"Tt ") && sep(13, b":") && sep(16, b":")) {
+        return false;
+    }
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    if day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+
+    let mut idx = 19;
+    if sep(idx, b".") {
+        idx += 1;
+        let start = idx;
+        while b.get(idx).is_some_and(u8::is_ascii_digit) {
+            idx += 1;
+        }
+        if idx == start {
+            return false;
+        }
+    }
+
+    match b.get(idx) {
+        Some(b'Z' | b'z') => idx + 1 == b.len(),
+        Some(b'+' | b'-') => {
+            idx + 6 == b.len()
+                && sep(idx + 3, b":")
+                && num(idx + 1..idx + 3).is_some_and(|h| h <= 23)
+                && num(idx + 4..idx + 6).is_some_and(|m| m <= 59)
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn is_false(b: &bool) -> bool {
     !b
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MessageBuilder {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
@@ -17,16 +159,99 @@ pub struct MessageBuilder {
     pub avatar_url: Option<String>,
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
     pub embeds: Vec<Embed>,
 
     #[serde(skip_serializing_if = "crate::models::is_false")]
+    #[serde(default)]
     pub tts: bool,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_mentions: Option<AllowedMentions>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_name: Option<String>,
+
+    #[serde(skip_serializing_if = "MessageFlags::is_empty")]
+    #[serde(default)]
+    pub flags: MessageFlags,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub components: Vec<Component>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<Poll>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_reference: Option<MessageReference>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageReference {
+    pub message_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_if_not_exists: Option<bool>,
+}
+
+impl MessageReference {
+    pub fn new(message_id: impl Into<String>) -> Self {
+        Self {
+            message_id: message_id.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn fail_if_not_exists(mut self, fail: bool) -> Self {
+        self.fail_if_not_exists = Some(fail);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MessageFlags(u64);
+
+impl MessageFlags {
+    pub const SUPPRESS_EMBEDS: Self = Self(1 << 2);
+    pub const SUPPRESS_NOTIFICATIONS: Self = Self(1 << 12);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for MessageFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for MessageFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AllowedMention {
     Users,
@@ -34,10 +259,39 @@ pub enum AllowedMention {
     Everyone,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct AllowedMentions {
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// Always sent, even when empty: an empty `parse` is how Discord is told to suppress
+    /// every mention that isn't listed explicitly.
+    #[serde(default)]
     pub parse: Vec<AllowedMention>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub users: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub roles: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replied_user: Option<bool>,
+}
+
+impl AllowedMentions {
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.parse.contains(&AllowedMention::Users) && !self.users.is_empty() {
+            return Err(crate::error::WebhookError::Request(
+                "allowed_mentions cannot combine parse \"users\" with a users list".to_string(),
+            ));
+        }
+        if self.parse.contains(&AllowedMention::Roles) && !self.roles.is_empty() {
+            return Err(crate::error::WebhookError::Request(
+                "allowed_mentions cannot combine parse \"roles\" with a roles list".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl MessageBuilder {
@@ -60,43 +314,229 @@ impl MessageBuilder {
         self
     }
 
-    pub fn embed(mut self, embed: Embed) -> Self {
-        self.embeds.push(embed);
+    pub fn embed(mut self, embed: impl Into<Embed>) -> Self {
+        self.embeds.push(embed.into());
         self
     }
 
-    pub fn embeds(mut self, embeds: Vec<Embed>) -> Self {
+    pub fn embeds(mut self, embeds: impl IntoIterator<Item = Embed>) -> Self {
         self.embeds.extend(embeds);
         self
     }
 
+    pub fn embeds_from(mut self, embeds: &[Embed]) -> Self {
+        self.embeds.extend_from_slice(embeds);
+        self
+    }
+
     pub fn tts(mut self, tts: bool) -> Self {
         self.tts = tts;
         self
     }
 
+    pub fn thread_name(mut self, name: impl Into<String>) -> Self {
+        self.thread_name = Some(name.into());
+        self
+    }
+
+    pub fn component(mut self, component: impl Into<Component>) -> Self {
+        self.components.push(component.into());
+        self
+    }
+
+    pub fn reply_to(mut self, message_id: impl Into<String>) -> Self {
+        self.message_reference = Some(MessageReference::new(message_id));
+        self
+    }
+
+    pub fn message_reference(mut self, reference: MessageReference) -> Self {
+        self.message_reference = Some(reference);
+        self
+    }
+
+    pub fn poll(mut self, poll: Poll) -> Self {
+        self.poll = Some(poll);
+        self
+    }
+
+    pub fn flags(mut self, flags: MessageFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
     pub fn allow_mention(mut self, mention: AllowedMention) -> Self {
-        if self.allowed_mentions.is_none() {
-            self.allowed_mentions = Some(AllowedMentions::default());
-        }
-        self.allowed_mentions.as_mut().unwrap().parse.push(mention);
+        self.allowed_mentions.get_or_insert_with(AllowedMentions::default).parse.push(mention);
         self
     }
 
-    pub fn build(self) -> crate::error::Result<Self> {
+    pub fn mention_user(mut self, id: impl Into<String>) -> Self {
+        self.allowed_mentions.get_or_insert_with(AllowedMentions::default).users.push(id.into());
+        self
+    }
+
+    pub fn mention_role(mut self, id: impl Into<String>) -> Self {
+        self.allowed_mentions.get_or_insert_with(AllowedMentions::default).roles.push(id.into());
+        self
+    }
+
+    pub fn mention_replied_user(mut self, mention: bool) -> Self {
+        self.allowed_mentions.get_or_insert_with(AllowedMentions::default).replied_user = Some(mention);
+        self
+    }
+
+    /// Suppresses every mention, including @everyone and @here.
+    pub fn no_mentions(mut self) -> Self {
+        self.allowed_mentions = Some(AllowedMentions::default());
+        self
+    }
+
+    pub fn merge(mut self, other: MessageBuilder) -> Self {
+        self.content = match (self.content, other.content) {
+            (Some(base), Some(extra)) => Some(format!("{}\n{}", base, extra)),
+            (base, extra) => extra.or(base),
+        };
+        self.username = other.username.or(self.username);
+        self.avatar_url = other.avatar_url.or(self.avatar_url);
+        self.thread_name = other.thread_name.or(self.thread_name);
+        self.poll = other.poll.or(self.poll);
+        self.message_reference = other.message_reference.or(self.message_reference);
+        self.tts |= other.tts;
+        self.flags |= other.flags;
+
+        self.embeds.extend(other.embeds);
+        self.embeds.truncate(MAX_EMBEDS);
+        self.components.extend(other.components);
+
+        self.allowed_mentions = match (self.allowed_mentions, other.allowed_mentions) {
+            (Some(mut base), Some(extra)) => {
+                for mention in extra.parse {
+                    if !base.parse.contains(&mention) {
+                        base.parse.push(mention);
+                    }
+                }
+                for id in extra.users {
+                    if !base.users.contains(&id) {
+                        base.users.push(id);
+                    }
+                }
+                for id in extra.roles {
+                    if !base.roles.contains(&id) {
+                        base.roles.push(id);
+                    }
+                }
+                base.replied_user = extra.replied_user.or(base.replied_user);
+                Some(base)
+            }
+            (base, extra) => extra.or(base),
+        };
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.content.as_deref().is_none_or(str::is_empty)
+            && self.embeds.is_empty()
+            && self.poll.is_none()
+            && self.components.is_empty()
+    }
+
+    pub fn requires_multipart(&self, attachments: &[Attachment]) -> bool {
+        !attachments.is_empty()
+    }
+
+    pub fn preview(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(ref username) = self.username {
+            lines.push(format!("[{}]", username));
+        }
         if let Some(ref content) = self.content {
-            if content.len() > 6000 {
-                return Err(crate::error::WebhookError::ContentTooLong(content.len()));
+            lines.push(content.clone());
+        }
+        for (idx, embed) in self.embeds.iter().enumerate() {
+            lines.push(format!("--- Embed {} ---", idx + 1));
+            if let Some(ref author) = embed.author {
+                lines.push(format!("  {}", author.name));
+            }
+            if let Some(ref title) = embed.title {
+                lines.push(format!("  {}", title));
+            }
+            if let Some(ref description) = embed.description {
+                lines.extend(description.lines().map(|line| format!("  {}", line)));
+            }
+            for field in &embed.fields {
+                lines.push(format!("    {}: {}", field.name, field.value));
+            }
+            if let Some(ref footer) = embed.footer {
+                lines.push(format!("  {}", footer.text));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn embed_character_count(&self) -> usize {
+        self.embeds.iter().map(Embed::character_count).sum()
+    }
+
+    /// Checks that every `attachment://name` reference in the embeds names one of
+    /// `attachments`.
+    pub(crate) fn check_attachment_references(&self, attachments: &[Attachment]) -> crate::error::Result<()> {
+        for name in self.embeds.iter().flat_map(Embed::attachment_references) {
+            if !attachments.iter().any(|attachment| attachment.filename() == name) {
+                return Err(crate::error::WebhookError::MissingAttachment(name.to_string()));
             }
         }
-        if self.embeds.len() > 10 {
+        Ok(())
+    }
+
+    /// Runs every check `build` does without consuming the builder.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if let Some(ref content) = self.content {
+            let count = content.chars().count();
+            if count > MAX_CONTENT_LENGTH {
+                return Err(crate::error::WebhookError::ContentTooLong(count));
+            }
+        }
+        if let Some(ref username) = self.username {
+            validate_username(username)?;
+        }
+        if let Some(ref allowed_mentions) = self.allowed_mentions {
+            allowed_mentions.validate()?;
+        }
+        if self.embeds.len() > MAX_EMBEDS {
             return Err(crate::error::WebhookError::Request("Too many embeds (max 10)".to_string()));
         }
+        if let Some(ref poll) = self.poll {
+            poll.validate()?;
+        }
+        for component in &self.components {
+            if !matches!(component, Component::ActionRow { .. }) {
+                return Err(crate::error::WebhookError::InvalidComponent(
+                    "top-level components must be action rows".to_string(),
+                ));
+            }
+            component.validate()?;
+        }
+        for (embed_index, embed) in self.embeds.iter().enumerate() {
+            embed.validate().map_err(|err| match err {
+                crate::error::WebhookError::TooManyFields { count, .. } => {
+                    crate::error::WebhookError::TooManyFields { embed_index, count }
+                }
+                other => other,
+            })?;
+        }
+        let total = self.embed_character_count();
+        if total > MAX_EMBED_TOTAL_LENGTH {
+            return Err(crate::error::WebhookError::EmbedTooLong(total));
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> crate::error::Result<Self> {
+        self.validate()?;
         Ok(self)
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Embed {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -132,10 +572,296 @@ pub struct Embed {
     pub author: Option<EmbedAuthor>,
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
     pub fields: Vec<EmbedField>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl Embed {
+    pub fn character_count(&self) -> usize {
+        let count = |s: &Option<String>| s.as_deref().map_or(0, |s| s.chars().count());
+        count(&self.title)
+            + count(&self.description)
+            + self
+                .fields
+                .iter()
+                .map(|f| f.name.chars().count() + f.value.chars().count())
+                .sum::<usize>()
+            + self.footer.as_ref().map_or(0, |f| f.text.chars().count())
+            + self.author.as_ref().map_or(0, |a| a.name.chars().count())
+    }
+
+    /// Deserializes an embed from JSON, rejecting unknown keys at any depth so typos don't
+    /// silently vanish, then checks Discord's length limits.
+    pub fn from_value(value: serde_json::Value) -> crate::error::Result<Embed> {
+        let mut unknown = Vec::new();
+        let embed: Embed = serde_ignored::deserialize(value, |path| {
+            // Option layers show up as `?` segments, e.g. `footer.?.icon`.
+            unknown.push(path.to_string().replace(".?", ""))
+        })?;
+        if !unknown.is_empty() {
+            return Err(crate::error::WebhookError::Request(format!(
+                "Unknown embed field: {}",
+                unknown.join(", ")
+            )));
+        }
+        embed.validate()?;
+        Ok(embed)
+    }
+
+    fn attachment_references(&self) -> impl Iterator<Item = &str> {
+        let media = [&self.image, &self.thumbnail, &self.video]
+            .into_iter()
+            .filter_map(|media| media.as_ref().map(|m| m.url.as_str()));
+        let footer_icon = self.footer.as_ref().and_then(|f| f.icon_url.as_deref());
+        let author_icon = self.author.as_ref().and_then(|a| a.icon_url.as_deref());
+        media
+            .chain(footer_icon)
+            .chain(author_icon)
+            .filter_map(|url| url.strip_prefix("attachment://"))
+    }
+
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.fields.len() > MAX_EMBED_FIELDS {
+            return Err(crate::error::WebhookError::TooManyFields {
+                embed_index: 0,
+                count: self.fields.len(),
+            });
+        }
+        if let Some(ref title) = self.title {
+            check_length("title", title, MAX_TITLE_LENGTH)?;
+        }
+        if let Some(ref description) = self.description {
+            check_length("description", description, MAX_DESCRIPTION_LENGTH)?;
+        }
+        for field in &self.fields {
+            check_length("field.name", &field.name, MAX_FIELD_NAME_LENGTH)?;
+            check_length("field.value", &field.value, MAX_FIELD_VALUE_LENGTH)?;
+        }
+        if let Some(ref footer) = self.footer {
+            check_length("footer.text", &footer.text, MAX_FOOTER_TEXT_LENGTH)?;
+        }
+        if let Some(ref author) = self.author {
+            check_length("author.name", &author.name, MAX_AUTHOR_NAME_LENGTH)?;
+        }
+        if let Some(ref timestamp) = self.timestamp
+            && !is_rfc3339(timestamp)
+        {
+            return Err(crate::error::WebhookError::Request("invalid timestamp".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn balance_inline_fields(&mut self) {
+        self.balance_inline_fields_to(3);
+    }
+
+    pub fn balance_inline_fields_to(&mut self, columns: usize) {
+        if columns < 2 {
+            return;
+        }
+        let mut idx = 0;
+        while idx < self.fields.len() {
+            if !self.fields[idx].inline {
+                idx += 1;
+                continue;
+            }
+            let start = idx;
+            while idx < self.fields.len() && self.fields[idx].inline {
+                idx += 1;
+            }
+            let remainder = (idx - start) % columns;
+            if remainder == 0 {
+                continue;
+            }
+            let padding = columns - remainder;
+            if self.fields.len() + padding > MAX_EMBED_FIELDS {
+                return;
+            }
+            for _ in 0..padding {
+                self.fields.insert(
+                    idx,
+                    EmbedField {
+                        name: "\u{200b}".to_string(),
+                        value: "\u{200b}".to_string(),
+                        inline: true,
+                    },
+                );
+            }
+            idx += padding;
+        }
+    }
+
+    pub fn from_map(title: impl Into<String>, map: &BTreeMap<String, String>) -> Self {
+        Self::from_map_overflowing(title, map)
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+    }
+
+    pub fn from_map_overflowing(title: impl Into<String>, map: &BTreeMap<String, String>) -> Vec<Self> {
+        let title = title.into();
+        let fields: Vec<EmbedField> = map
+            .iter()
+            .map(|(name, value)| EmbedField {
+                name: truncate_chars(name, MAX_FIELD_NAME_LENGTH),
+                value: truncate_chars(value, MAX_FIELD_VALUE_LENGTH),
+                inline: false,
+            })
+            .collect();
+
+        if fields.is_empty() {
+            return vec![Self {
+                title: Some(title),
+                ..Default::default()
+            }];
+        }
+
+        fields
+            .chunks(MAX_EMBED_FIELDS)
+            .enumerate()
+            .map(|(idx, chunk)| Self {
+                title: (idx == 0).then(|| title.clone()),
+                fields: chunk.to_vec(),
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmbedBuilder {
+    embed: Embed,
+}
+
+impl EmbedBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.embed.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.embed.description = Some(description.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.embed.url = Some(url.into());
+        self
+    }
+
+    pub fn title_link(mut self, text: impl Into<String>, url: impl Into<String>) -> crate::error::Result<Self> {
+        let url = url.into();
+        let valid = url
+            .parse::<hyper::Uri>()
+            .is_ok_and(|uri| matches!(uri.scheme_str(), Some("http" | "https")) && uri.host().is_some());
+        if !valid {
+            return Err(crate::error::WebhookError::InvalidEmbedUrl(url));
+        }
+        self.embed.title = Some(text.into());
+        self.embed.url = Some(url);
+        Ok(self)
+    }
+
+    pub fn color(mut self, color: impl Into<u32>) -> Self {
+        self.embed.color = Some(color.into());
+        self
+    }
+
+    pub fn color_hex(mut self, hex: &str) -> crate::error::Result<Self> {
+        self.embed.color = Some(crate::colors::parse_hex(hex)?);
+        Ok(self)
+    }
+
+    pub fn timestamp(mut self, timestamp: impl Into<String>) -> Self {
+        self.embed.timestamp = Some(timestamp.into());
+        self
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn timestamp_dt(self, timestamp: chrono::DateTime<chrono::Utc>) -> Self {
+        self.timestamp(timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn timestamp_now(self) -> Self {
+        self.timestamp_dt(chrono::Utc::now())
+    }
+
+    pub fn footer(mut self, text: impl Into<String>) -> Self {
+        self.embed.footer = Some(EmbedFooter {
+            text: text.into(),
+            icon_url: None,
+            proxy_icon_url: None,
+        });
+        self
+    }
+
+    /// Sets a footer with an icon, which may be an uploaded file (`attachment://icon.png`).
+    pub fn footer_icon(mut self, text: impl Into<String>, icon_url: impl Into<String>) -> Self {
+        self.embed.footer = Some(EmbedFooter {
+            text: text.into(),
+            icon_url: Some(icon_url.into()),
+            proxy_icon_url: None,
+        });
+        self
+    }
+
+    pub fn author(mut self, name: impl Into<String>) -> Self {
+        self.embed.author = Some(EmbedAuthor {
+            name: name.into(),
+            url: None,
+            icon_url: None,
+            proxy_icon_url: None,
+        });
+        self
+    }
+
+    /// Sets an author with an icon, which may be an uploaded file (`attachment://icon.png`).
+    pub fn author_icon(mut self, name: impl Into<String>, icon_url: impl Into<String>) -> Self {
+        self.embed.author = Some(EmbedAuthor {
+            name: name.into(),
+            url: None,
+            icon_url: Some(icon_url.into()),
+            proxy_icon_url: None,
+        });
+        self
+    }
+
+    pub fn image(mut self, url: impl Into<String>) -> Self {
+        self.embed.image = Some(EmbedMedia::new(url));
+        self
+    }
+
+    pub fn thumbnail(mut self, url: impl Into<String>) -> Self {
+        self.embed.thumbnail = Some(EmbedMedia::new(url));
+        self
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
+        self.embed.fields.push(EmbedField {
+            name: name.into(),
+            value: value.into(),
+            inline,
+        });
+        self
+    }
+
+    pub fn build(self) -> Embed {
+        self.embed
+    }
+}
+
+impl From<EmbedBuilder> for Embed {
+    fn from(builder: EmbedBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmbedFooter {
     pub text: String,
 
@@ -146,7 +872,7 @@ pub struct EmbedFooter {
     pub proxy_icon_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmbedMedia {
     pub url: String,
 
@@ -160,7 +886,18 @@ pub struct EmbedMedia {
     pub width: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl EmbedMedia {
+    fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            proxy_url: None,
+            height: None,
+            width: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmbedProvider {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -169,7 +906,7 @@ pub struct EmbedProvider {
     pub url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmbedAuthor {
     pub name: String,
 
@@ -183,7 +920,7 @@ pub struct EmbedAuthor {
     pub proxy_icon_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmbedField {
     pub name: String,
     pub value: String,
@@ -193,14 +930,176 @@ pub struct EmbedField {
     pub inline: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Attachment {
     pub path: PathBuf,
     pub description: Option<String>,
+    pub data: Option<Vec<u8>>,
+    /// Stable id used for the `files[id]` part and the `attachments` payload entry.
+    /// Defaults to the attachment's position in the list; embeds still reference the
+    /// file by name (`attachment://filename`).
+    pub id: Option<u64>,
+    pub content_type: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+impl Attachment {
+    pub fn from_bytes(filename: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            path: PathBuf::from(filename.into()),
+            data: Some(bytes.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns one attachment per file matching `pattern`, in path order. Fails with
+    /// `TooManyAttachments` if more files match than fit in one message.
+    pub fn from_glob(pattern: &str) -> crate::error::Result<Vec<Self>> {
+        let paths = glob::glob(pattern)
+            .map_err(|e| crate::error::WebhookError::Request(format!("Invalid glob pattern: {}", e)))?;
+        let mut attachments = Vec::new();
+        for path in paths {
+            let path = path.map_err(std::io::Error::from)?;
+            if path.is_file() {
+                attachments.push(Self {
+                    path,
+                    ..Default::default()
+                });
+            }
+        }
+        if attachments.len() > MAX_ATTACHMENTS {
+            return Err(crate::error::WebhookError::TooManyAttachments(attachments.len()));
+        }
+        Ok(attachments)
+    }
+
+    /// Overrides the guessed MIME type. Values that are not `type/subtype` fail the send.
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn filename(&self) -> String {
+        self.path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("attachment")
+            .to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AttachmentMetadata {
+    pub(crate) id: u64,
+    pub(crate) filename: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) description: Option<String>,
+}
+
+impl PartialEq for Attachment {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.data == other.data
+    }
+}
+
+impl Eq for Attachment {}
+
+impl std::hash::Hash for Attachment {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.path.hash(state);
+        self.data.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct WebhookResponse {
     pub status_code: u16,
     pub body: String,
+    pub diagnostics: std::collections::HashMap<String, String>,
+    pub headers: std::collections::HashMap<String, String>,
+}
+
+impl WebhookResponse {
+    pub fn is_no_content(&self) -> bool {
+        self.status_code == 204
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_ascii_lowercase()).map(String::as_str)
+    }
+
+    pub fn parse_message(&self) -> crate::error::Result<SentMessage> {
+        serde_json::from_str(&self.body)
+            .map_err(|e| crate::error::WebhookError::InvalidResponse(format!("not a message object: {}", e)))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SentMessage {
+    pub id: String,
+    pub channel_id: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+/// The editable parts of a fetched message. Polls, replies and thread names cannot be
+/// changed after sending, so they are not read back. Link, image and video previews that
+/// Discord generated itself are dropped rather than resent as rich embeds, and component
+/// types this crate does not model are skipped.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct EditableMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    embeds: Vec<serde_json::Value>,
+    #[serde(default)]
+    tts: bool,
+    #[serde(default)]
+    components: Vec<serde_json::Value>,
+    #[serde(default)]
+    flags: MessageFlags,
+}
+
+impl EditableMessage {
+    pub(crate) fn into_builder(self) -> crate::error::Result<MessageBuilder> {
+        let embeds = self
+            .embeds
+            .into_iter()
+            .filter(|embed| embed.get("type").is_none_or(|kind| kind == "rich"))
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<Embed>, _>>()
+            .map_err(|e| crate::error::WebhookError::InvalidResponse(format!("invalid embed: {}", e)))?;
+        let components = self
+            .components
+            .into_iter()
+            .filter_map(|component| serde_json::from_value(component).ok())
+            .collect();
+        // SUPPRESS_EMBEDS is the only flag an edit may set.
+        let flags = if self.flags.contains(MessageFlags::SUPPRESS_EMBEDS) {
+            MessageFlags::SUPPRESS_EMBEDS
+        } else {
+            MessageFlags::empty()
+        };
+        Ok(MessageBuilder {
+            content: self.content,
+            embeds,
+            tts: self.tts,
+            components,
+            flags,
+            ..MessageBuilder::default()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookInfo {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: u8,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub guild_id: Option<String>,
 }
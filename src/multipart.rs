@@ -1,10 +1,25 @@
 use crate::models::Attachment;
+use crate::transport::StreamingBody;
+use futures_util::{StreamExt, TryStreamExt};
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::{Bytes, Frame};
 use std::borrow::Cow;
-use std::fs::File;
-use std::io::Read;
+use std::path::PathBuf;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+
+const DEFAULT_BOUNDARY_PREFIX: &str = "DiscordWebhookBoundary";
+const BOUNDARY_RANDOM_BYTES: usize = 16;
+const MAX_BOUNDARY_PREFIX_LEN: usize = 70 - BOUNDARY_RANDOM_BYTES * 2;
+pub(crate) const DEFAULT_MAX_UPLOAD_SIZE: u64 = 25 * 1024 * 1024;
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
 
 pub struct MultipartBuilder {
     parts: Vec<(String, MultipartPart)>,
+    boundary_prefix: String,
+    max_upload_size: u64,
+    upload_size: u64,
+    streaming: bool,
 }
 
 pub enum MultipartPart {
@@ -12,41 +27,111 @@ pub enum MultipartPart {
     File(AttachmentFile),
 }
 
+impl MultipartPart {
+    fn contains(&self, needle: &[u8]) -> bool {
+        let haystack = match self {
+            MultipartPart::String(s) => s.as_bytes(),
+            MultipartPart::File(AttachmentFile {
+                content: FileContent::Memory(content),
+                ..
+            }) => content,
+            MultipartPart::File(_) => return false,
+        };
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+}
+
 pub struct AttachmentFile {
     filename: String,
-    content: Vec<u8>,
+    content: FileContent,
     mime_type: Option<String>,
 }
 
+pub enum FileContent {
+    Memory(Vec<u8>),
+    Disk { path: PathBuf, len: u64 },
+}
+
+enum Segment {
+    Bytes(Bytes),
+    File(PathBuf),
+}
+
 impl MultipartBuilder {
     pub fn new() -> Self {
-        Self { parts: Vec::new() }
+        Self {
+            parts: Vec::new(),
+            boundary_prefix: DEFAULT_BOUNDARY_PREFIX.to_string(),
+            max_upload_size: DEFAULT_MAX_UPLOAD_SIZE,
+            upload_size: 0,
+            streaming: false,
+        }
+    }
+
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    pub fn with_max_upload_size(mut self, bytes: u64) -> Self {
+        self.max_upload_size = bytes;
+        self
+    }
+
+    pub fn with_boundary_prefix(mut self, prefix: impl Into<String>) -> crate::error::Result<Self> {
+        let prefix = prefix.into();
+        validate_boundary_prefix(&prefix)?;
+        self.boundary_prefix = prefix;
+        Ok(self)
     }
 
-    pub fn add_json<T: serde::Serialize>(mut self, name: String, value: &T) -> crate::error::Result<Self> {
-        let json = serde_json::to_string(value)?;
+    pub fn add_json<T: serde::Serialize>(
+        mut self,
+        name: String,
+        value: &T,
+        ascii_only: bool,
+    ) -> crate::error::Result<Self> {
+        let json = String::from_utf8_lossy(&crate::json::to_vec(value, ascii_only)?).into_owned();
         self.parts.push((name, MultipartPart::String(json)));
         Ok(self)
     }
 
-    pub fn add_attachment(mut self, index: usize, attachment: Attachment) -> crate::error::Result<Self> {
-        let mut file = File::open(&attachment.path)?;
-        let mut content = Vec::new();
-        file.read_to_end(&mut content)?;
+    pub async fn add_attachment(mut self, id: u64, attachment: Attachment) -> crate::error::Result<Self> {
+        if let Some(ref content_type) = attachment.content_type {
+            validate_content_type(content_type)?;
+        }
+        let filename = attachment.filename();
+        let size = match attachment.data {
+            Some(ref data) => data.len() as u64,
+            None => tokio::fs::metadata(&attachment.path)
+                .await
+                .map_err(|source| attachment_io(&attachment.path, source))?
+                .len(),
+        };
+        self.reserve_upload(size)?;
 
-        let filename = attachment
-            .path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("attachment")
-            .to_string();
+        let mime_type = attachment.content_type.or_else(|| {
+            mime_guess::from_path(&attachment.path)
+                .first_raw()
+                .map(String::from)
+        });
 
-        let mime_type = mime_guess::from_path(&attachment.path)
-            .first_raw()
-            .map(String::from);
+        let content = match attachment.data {
+            Some(data) => FileContent::Memory(data),
+            None if self.streaming => FileContent::Disk {
+                path: attachment.path,
+                len: size,
+            },
+            None => {
+                let content = tokio::fs::read(&attachment.path)
+                    .await
+                    .map_err(|source| attachment_io(&attachment.path, source))?;
+                FileContent::Memory(content)
+            }
+        };
 
         self.parts.push((
-            format!("files[{}]", index),
+            format!("files[{}]", id),
             MultipartPart::File(AttachmentFile {
                 filename,
                 content,
@@ -57,41 +142,160 @@ impl MultipartBuilder {
         Ok(self)
     }
 
+    fn reserve_upload(&mut self, size: u64) -> crate::error::Result<()> {
+        let total = self.upload_size + size;
+        if total > self.max_upload_size {
+            return Err(crate::error::WebhookError::AttachmentTooLarge {
+                size: total,
+                max: self.max_upload_size,
+            });
+        }
+        self.upload_size = total;
+        Ok(())
+    }
+
     pub fn build(self) -> crate::error::Result<(Vec<u8>, String)> {
-        let boundary = generate_boundary();
-        let mut body = Vec::new();
+        let (segments, content_type, len) = self.into_segments()?;
+        let mut body = Vec::with_capacity(len as usize);
+        for segment in segments {
+            match segment {
+                Segment::Bytes(bytes) => body.extend_from_slice(&bytes),
+                Segment::File(_) => {
+                    return Err(crate::error::WebhookError::Request(
+                        "Streamed attachments need build_streaming".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok((body, content_type))
+    }
+
+    /// Like `build`, but files added in streaming mode are read in chunks while the
+    /// body is sent. Returns the body, its content type and its exact length.
+    pub fn build_streaming(self) -> crate::error::Result<(StreamingBody, String, u64)> {
+        let (segments, content_type, len) = self.into_segments()?;
+        let stream = futures_util::stream::iter(segments)
+            .flat_map(|segment| match segment {
+                Segment::Bytes(bytes) => futures_util::stream::once(std::future::ready(Ok(bytes))).boxed(),
+                Segment::File(path) => file_chunks(path).boxed(),
+            })
+            .map_ok(Frame::data);
+        Ok((StreamBody::new(stream).boxed_unsync(), content_type, len))
+    }
+
+    fn into_segments(self) -> crate::error::Result<(Vec<Segment>, String, u64)> {
+        let boundary = loop {
+            let candidate = generate_boundary(&self.boundary_prefix)?;
+            if !self.parts.iter().any(|(_, part)| part.contains(candidate.as_bytes())) {
+                break candidate;
+            }
+        };
+        let mut segments = Vec::new();
+        let mut pending = Vec::new();
+        let mut len = 0u64;
 
         for (name, part) in self.parts {
-            write_boundary(&mut body, &boundary)?;
-            write_part_header(&mut body, &name, &part)?;
+            write_boundary(&mut pending, &boundary)?;
+            write_part_header(&mut pending, &name, &part)?;
 
             match part {
-                MultipartPart::String(s) => {
-                    body.extend_from_slice(s.as_bytes());
-                }
-                MultipartPart::File(file) => {
-                    body.extend_from_slice(&file.content);
-                }
+                MultipartPart::String(s) => pending.extend_from_slice(s.as_bytes()),
+                MultipartPart::File(file) => match file.content {
+                    FileContent::Memory(content) => pending.extend_from_slice(&content),
+                    FileContent::Disk { path, len: file_len } => {
+                        len += pending.len() as u64 + file_len;
+                        segments.push(Segment::Bytes(Bytes::from(std::mem::take(&mut pending))));
+                        segments.push(Segment::File(path));
+                    }
+                },
             }
-            body.extend_from_slice(b"\r\n");
+            pending.extend_from_slice(b"\r\n");
         }
 
-        body.extend_from_slice(b"--");
-        body.extend_from_slice(boundary.as_bytes());
-        body.extend_from_slice(b"--\r\n");
+        pending.extend_from_slice(b"--");
+        pending.extend_from_slice(boundary.as_bytes());
+        pending.extend_from_slice(b"--\r\n");
+        len += pending.len() as u64;
+        segments.push(Segment::Bytes(Bytes::from(pending)));
 
         let content_type = format!("multipart/form-data; boundary={}", boundary);
-        Ok((body, content_type))
+        Ok((segments, content_type, len))
+    }
+}
+
+pub(crate) fn validate_boundary_prefix(prefix: &str) -> crate::error::Result<()> {
+    // Only RFC 2045 token characters, since the boundary parameter is written unquoted.
+    let legal = |c: char| c.is_ascii_alphanumeric() || "'+_-.".contains(c);
+    if prefix.is_empty() || prefix.len() > MAX_BOUNDARY_PREFIX_LEN || !prefix.chars().all(legal) {
+        return Err(crate::error::WebhookError::Request(format!(
+            "Invalid multipart boundary prefix: {:?}",
+            prefix
+        )));
+    }
+    Ok(())
+}
+
+/// Accepts `type/subtype` with optional parameters; control characters are rejected so a
+/// caller-supplied value cannot add part headers.
+fn validate_content_type(content_type: &str) -> crate::error::Result<()> {
+    let token = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c));
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    let valid = !content_type.chars().any(|c| c.is_control() || !c.is_ascii())
+        && essence.split_once('/').is_some_and(|(kind, subtype)| token(kind) && token(subtype));
+    if !valid {
+        return Err(crate::error::WebhookError::Request(format!(
+            "Invalid attachment content type: {:?}",
+            content_type
+        )));
     }
+    Ok(())
 }
 
-fn generate_boundary() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
-    format!("DiscordWebhookBoundary{}", timestamp)
+fn generate_boundary(prefix: &str) -> crate::error::Result<String> {
+    let mut bytes = [0u8; BOUNDARY_RANDOM_BYTES];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| crate::error::WebhookError::Request(format!("Failed to generate multipart boundary: {}", e)))?;
+    let mut boundary = String::with_capacity(prefix.len() + BOUNDARY_RANDOM_BYTES * 2);
+    boundary.push_str(prefix);
+    for byte in bytes {
+        boundary.push_str(&format!("{:02x}", byte));
+    }
+    Ok(boundary)
+}
+
+fn attachment_io(path: &std::path::Path, source: std::io::Error) -> crate::error::WebhookError {
+    crate::error::WebhookError::AttachmentIo {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn file_chunks(path: PathBuf) -> impl futures_util::Stream<Item = std::io::Result<Bytes>> + Send {
+    enum State {
+        Closed(PathBuf),
+        Open(File),
+        Done,
+    }
+
+    futures_util::stream::unfold(State::Closed(path), |state| async move {
+        let mut file = match state {
+            State::Closed(path) => match File::open(&path).await {
+                Ok(file) => file,
+                Err(err) => return Some((Err(err), State::Done)),
+            },
+            State::Open(file) => file,
+            State::Done => return None,
+        };
+        let mut chunk = vec![0u8; STREAM_CHUNK_SIZE];
+        match file.read(&mut chunk).await {
+            Ok(0) => None,
+            Ok(n) => {
+                chunk.truncate(n);
+                Some((Ok(Bytes::from(chunk)), State::Open(file)))
+            }
+            Err(err) => Some((Err(err), State::Done)),
+        }
+    })
 }
 
 fn write_boundary<W: std::io::Write>(writer: &mut W, boundary: &str) -> std::io::Result<()> {
@@ -122,15 +326,15 @@ fn write_part_header<W: std::io::Write>(
                 escape_filename(&file.filename)
             )?;
             if let Some(mime) = &file.mime_type {
-                writeln!(writer, "Content-Type: {}", mime)?;
+                write!(writer, "Content-Type: {}\r\n", mime)?;
             }
-            writeln!(writer)?;
+            write!(writer, "\r\n")?;
         }
     }
     Ok(())
 }
 
-fn escape_form_data(s: &str) -> Cow<str> {
+fn escape_form_data(s: &str) -> Cow<'_, str> {
     if s.chars().any(|c| c == '"' || c == '\r' || c == '\n') {
         s.replace(['\r', '\n'], "").replace('"', "\\\"").into()
     } else {
@@ -138,7 +342,7 @@ fn escape_form_data(s: &str) -> Cow<str> {
     }
 }
 
-fn escape_filename(s: &str) -> Cow<str> {
+fn escape_filename(s: &str) -> Cow<'_, str> {
     s.replace('\\', "\\\\")
         .replace('"', "\\\"")
         .into()
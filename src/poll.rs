@@ -0,0 +1,81 @@
+use crate::components::ButtonEmoji;
+use crate::error::{Result, WebhookError};
+use serde::{Deserialize, Serialize};
+
+pub(crate) const MAX_POLL_ANSWERS: usize = 10;
+pub(crate) const MAX_POLL_QUESTION_LENGTH: usize = 300;
+pub(crate) const MAX_POLL_ANSWER_LENGTH: usize = 55;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollMedia {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<ButtonEmoji>,
+}
+
+impl PollMedia {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            emoji: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollAnswer {
+    pub poll_media: PollMedia,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poll {
+    pub question: PollMedia,
+    #[serde(default)]
+    pub answers: Vec<PollAnswer>,
+    pub duration: u32,
+    #[serde(default)]
+    pub allow_multiselect: bool,
+}
+
+impl Poll {
+    pub fn new(question: impl Into<String>, duration_hours: u32) -> Self {
+        Self {
+            question: PollMedia::new(question),
+            duration: duration_hours,
+            ..Default::default()
+        }
+    }
+
+    pub fn answer(mut self, text: impl Into<String>) -> Self {
+        self.answers.push(PollAnswer {
+            poll_media: PollMedia::new(text),
+        });
+        self
+    }
+
+    pub fn answer_with_emoji(mut self, text: impl Into<String>, emoji: impl Into<ButtonEmoji>) -> Self {
+        self.answers.push(PollAnswer {
+            poll_media: PollMedia {
+                text: text.into(),
+                emoji: Some(emoji.into()),
+            },
+        });
+        self
+    }
+
+    pub fn allow_multiselect(mut self, allow: bool) -> Self {
+        self.allow_multiselect = allow;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.answers.len() > MAX_POLL_ANSWERS {
+            return Err(WebhookError::Request("Too many poll answers (max 10)".to_string()));
+        }
+        crate::models::check_length("poll question", &self.question.text, MAX_POLL_QUESTION_LENGTH)?;
+        for answer in &self.answers {
+            crate::models::check_length("poll answer", &answer.poll_media.text, MAX_POLL_ANSWER_LENGTH)?;
+        }
+        Ok(())
+    }
+}
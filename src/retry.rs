@@ -0,0 +1,102 @@
+use crate::error::{Result, WebhookError};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub retry_rate_limited: bool,
+    pub retry_server_errors: bool,
+    pub retry_connection_errors: bool,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+    pub max_elapsed: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_rate_limited: true,
+            retry_server_errors: true,
+            retry_connection_errors: true,
+            base_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_elapsed: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn status_only(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            retry_connection_errors: false,
+            max_elapsed: Duration::MAX,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) async fn run<T, F, Fut>(&self, mut attempt_fn: F, on_retry: impl Fn(u32, Duration)) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let err = match attempt_fn().await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+
+            if attempt >= self.max_attempts || !self.should_retry(&err) {
+                return Err(err);
+            }
+
+            let delay = self.delay_for(&err, attempt - 1);
+            if started.elapsed().saturating_add(delay) > self.max_elapsed {
+                return Err(err);
+            }
+
+            on_retry(attempt, delay);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    fn should_retry(&self, err: &WebhookError) -> bool {
+        match err {
+            WebhookError::Status { status, .. } if status.as_u16() == 429 => self.retry_rate_limited,
+            WebhookError::Status { status, .. } => status.is_server_error() && self.retry_server_errors,
+            WebhookError::Http(_)
+            | WebhookError::Io(_)
+            | WebhookError::TokioTls(_)
+            | WebhookError::Timeout(_) => self.retry_connection_errors,
+            #[cfg(feature = "native-tls")]
+            WebhookError::Tls(_) => self.retry_connection_errors,
+            _ => false,
+        }
+    }
+
+    fn delay_for(&self, err: &WebhookError, retry: u32) -> Duration {
+        err.retry_after().unwrap_or_else(|| self.backoff(retry))
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let exponential = self
+            .base_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        let jitter = exponential.mul_f64(crate::random::random_fraction() / 2.0);
+        exponential + jitter
+    }
+}
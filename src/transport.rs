@@ -0,0 +1,446 @@
+use crate::error::{Result, WebhookError};
+use base64::Engine;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::client::conn::http1::{handshake, SendRequest};
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpSocket, TcpStream};
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!("either the `native-tls` or the `rustls` feature must be enabled");
+
+pub(crate) const DEFAULT_CONNECTION_LIFETIME: Duration = Duration::from_secs(300);
+const CONNECTION_ERROR_GRACE: Duration = Duration::from_millis(100);
+
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<Response<Bytes>>> + Send + 'a>>;
+
+/// Request body produced by streaming multipart uploads.
+pub type StreamingBody = UnsyncBoxBody<Bytes, std::io::Error>;
+
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    fn send(&self, request: Request<Bytes>) -> TransportFuture<'_>;
+
+    /// Sends a request whose body is produced on demand. The default collects the
+    /// body and forwards it to `send`.
+    fn send_streaming(&self, request: Request<StreamingBody>) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = body.collect().await?.to_bytes();
+            self.send(Request::from_parts(parts, body)).await
+        })
+    }
+}
+
+pub(crate) fn full_body(bytes: Bytes) -> StreamingBody {
+    Full::new(bytes).map_err(|never| match never {}).boxed_unsync()
+}
+
+fn tls_error<E: std::fmt::Display>(err: E) -> WebhookError {
+    WebhookError::TokioTls(err.to_string())
+}
+
+const MAX_PROXY_RESPONSE_LEN: usize = 8192;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Proxy {
+    pub(crate) addr: String,
+    pub(crate) authorization: Option<String>,
+}
+
+impl Proxy {
+    pub(crate) fn parse(url: &str) -> Result<Self> {
+        let uri: hyper::Uri = url
+            .parse()
+            .map_err(|_| WebhookError::Request(format!("Invalid proxy URL: {}", url)))?;
+        let authority = uri
+            .authority()
+            .ok_or_else(|| WebhookError::Request(format!("Proxy URL has no host: {}", url)))?;
+        if !matches!(uri.scheme_str(), None | Some("http")) {
+            return Err(WebhookError::Request(format!("Unsupported proxy scheme: {}", url)));
+        }
+        let addr = format!("{}:{}", authority.host(), authority.port_u16().unwrap_or(80));
+
+        let authorization = authority.as_str().rsplit_once('@').map(|(userinfo, _)| {
+            let (user, pass) = userinfo.split_once(':').unwrap_or((userinfo, ""));
+            let credentials = format!("{}:{}", percent_decode(user), percent_decode(pass));
+            format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials))
+        });
+
+        Ok(Self { addr, authorization })
+    }
+
+    fn from_env() -> Option<Self> {
+        let url = std::env::var("HTTPS_PROXY")
+            .or_else(|_| std::env::var("https_proxy"))
+            .ok()
+            .filter(|url| !url.is_empty())?;
+        Self::parse(&url).ok()
+    }
+
+    pub(crate) async fn tunnel<S>(&self, stream: &mut S, target: &str) -> Result<()>
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+        if let Some(authorization) = &self.authorization {
+            request.push_str(&format!("Proxy-Authorization: {}\r\n", authorization));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_PROXY_RESPONSE_LEN {
+                return Err(WebhookError::Request("Proxy response headers too large".to_string()));
+            }
+            let byte = stream.read_u8().await?;
+            response.push(byte);
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        let status_line = response.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(WebhookError::Request(format!("Proxy CONNECT failed: {}", status_line))),
+        }
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = s.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+pub(crate) fn connect_target(uri: &hyper::Uri) -> Result<(String, String)> {
+    let host = uri
+        .host()
+        .ok_or_else(|| WebhookError::Request("Missing host".to_string()))?;
+    let host = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+    let port = uri.port_u16().unwrap_or(443);
+    let addr = match host.parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::new(ip, port).to_string(),
+        Err(_) => format!("{}:{}", host, port),
+    };
+    Ok((host.to_string(), addr))
+}
+
+pub(crate) async fn connect_tcp(addr: &str, local_address: Option<IpAddr>) -> Result<TcpStream> {
+    let Some(local) = local_address else {
+        return Ok(TcpStream::connect(addr).await?);
+    };
+
+    let mut last_err = None;
+    for remote in lookup_host(addr).await?.filter(|remote| remote.is_ipv4() == local.is_ipv4()) {
+        match connect_from(local, remote).await {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err
+        .map(WebhookError::Io)
+        .unwrap_or_else(|| WebhookError::Request(format!("No address of {} matches local address {}", addr, local))))
+}
+
+async fn connect_from(local: IpAddr, remote: SocketAddr) -> std::io::Result<TcpStream> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::for_address(remote),
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::new(local, 0).into())?;
+    let socket = TcpSocket::from_std_stream(socket.into());
+    socket.connect(remote).await
+}
+
+#[cfg(feature = "rustls")]
+pub(crate) fn check_root_cert(pem: &[u8]) -> Result<()> {
+    rustls_root_certs(pem).map(drop)
+}
+
+#[cfg(feature = "rustls")]
+fn rustls_root_certs(pem: &[u8]) -> Result<Vec<tokio_rustls::rustls::pki_types::CertificateDer<'static>>> {
+    use tokio_rustls::rustls::RootCertStore;
+    use tokio_rustls::rustls::pki_types::CertificateDer;
+    use tokio_rustls::rustls::pki_types::pem::PemObject;
+
+    let invalid = |e: &dyn std::fmt::Display| WebhookError::InvalidCertificate(e.to_string());
+    let certs = CertificateDer::pem_slice_iter(pem)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| invalid(&e))?;
+    if certs.is_empty() {
+        return Err(invalid(&"no certificate found in PEM"));
+    }
+    let mut store = RootCertStore::empty();
+    for cert in &certs {
+        store.add(cert.clone()).map_err(|e| invalid(&e))?;
+    }
+    Ok(certs)
+}
+
+#[cfg(feature = "rustls")]
+async fn tls_connect(
+    host: &str,
+    stream: TcpStream,
+    root_certs: &[Vec<u8>],
+) -> Result<tokio_rustls::client::TlsStream<TcpStream>> {
+    use std::sync::{Arc, OnceLock};
+    use tokio_rustls::rustls::pki_types::ServerName;
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+    use tokio_rustls::TlsConnector;
+
+    fn client_config(roots: RootCertStore) -> Arc<ClientConfig> {
+        Arc::new(
+            ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        )
+    }
+    let default_roots = || RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    let config = if root_certs.is_empty() {
+        CONFIG.get_or_init(|| client_config(default_roots())).clone()
+    } else {
+        let mut roots = default_roots();
+        for pem in root_certs {
+            for cert in rustls_root_certs(pem)? {
+                roots.add(cert).map_err(tls_error)?;
+            }
+        }
+        client_config(roots)
+    };
+
+    let server_name = ServerName::try_from(host.to_string()).map_err(tls_error)?;
+    TlsConnector::from(config)
+        .connect(server_name, stream)
+        .await
+        .map_err(tls_error)
+}
+
+#[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+pub(crate) fn check_root_cert(pem: &[u8]) -> Result<()> {
+    native_tls::Certificate::from_pem(pem).map_err(|e| WebhookError::InvalidCertificate(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+async fn tls_connect(
+    host: &str,
+    stream: TcpStream,
+    root_certs: &[Vec<u8>],
+) -> Result<tokio_native_tls::TlsStream<TcpStream>> {
+    let mut builder = native_tls::TlsConnector::builder();
+    for pem in root_certs {
+        builder.add_root_certificate(native_tls::Certificate::from_pem(pem)?);
+    }
+    let connector = tokio_native_tls::TlsConnector::from(builder.build()?);
+    connector.connect(host, stream).await.map_err(tls_error)
+}
+
+#[derive(Debug)]
+struct PooledConnection<C> {
+    key: String,
+    conn: C,
+    established: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct ConnectionPool<C> {
+    slot: Mutex<Option<PooledConnection<C>>>,
+}
+
+impl<C> Default for ConnectionPool<C> {
+    fn default() -> Self {
+        Self { slot: Mutex::new(None) }
+    }
+}
+
+impl<C> ConnectionPool<C> {
+    pub(crate) fn checkout(&self, key: &str, lifetime: Duration) -> Option<(C, Instant)> {
+        let pooled = self.slot.lock().unwrap().take()?;
+        if pooled.key != key || pooled.established.elapsed() >= lifetime {
+            return None;
+        }
+        Some((pooled.conn, pooled.established))
+    }
+
+    pub(crate) fn checkin(&self, key: String, conn: C, established: Instant) {
+        *self.slot.lock().unwrap() = Some(PooledConnection {
+            key,
+            conn,
+            established,
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TransportConfig {
+    pub(crate) connection_lifetime: Duration,
+    pub(crate) local_address: Option<IpAddr>,
+    pub(crate) proxy: Option<Proxy>,
+    pub(crate) root_certs: Vec<Vec<u8>>,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            connection_lifetime: DEFAULT_CONNECTION_LIFETIME,
+            local_address: None,
+            proxy: None,
+            root_certs: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Connection {
+    sender: SendRequest<StreamingBody>,
+    failure: oneshot::Receiver<hyper::Error>,
+}
+
+impl Connection {
+    pub(crate) async fn handshake<T>(io: T) -> Result<Self>
+    where
+        T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    {
+        let (sender, conn) = handshake(TokioIo::new(io)).await?;
+        let (tx, failure) = oneshot::channel();
+        tokio::spawn(async move {
+            if let Err(err) = conn.await {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(error = %err, "connection closed with an error");
+                let _ = tx.send(err);
+            }
+        });
+        Ok(Self { sender, failure })
+    }
+
+    pub(crate) async fn send(&mut self, req: Request<StreamingBody>) -> Result<Response<Bytes>> {
+        let result = async {
+            let resp = self.sender.send_request(req).await?;
+            let (parts, body) = resp.into_parts();
+            let body_bytes = body.collect().await?.to_bytes();
+            Ok::<_, hyper::Error>(Response::from_parts(parts, body_bytes))
+        }
+        .await;
+
+        match result {
+            Ok(resp) => Ok(resp),
+            Err(err) if err.is_canceled() || err.is_closed() => {
+                Err(WebhookError::Http(self.connection_error().await.unwrap_or(err)))
+            }
+            Err(err) => Err(WebhookError::Http(err)),
+        }
+    }
+
+    async fn connection_error(&mut self) -> Option<hyper::Error> {
+        tokio::time::timeout(CONNECTION_ERROR_GRACE, &mut self.failure)
+            .await
+            .ok()?
+            .ok()
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TlsTransport {
+    pub(crate) config: TransportConfig,
+    pool: ConnectionPool<Connection>,
+}
+
+impl TlsTransport {
+    pub(crate) fn new(config: TransportConfig) -> Self {
+        Self {
+            config,
+            pool: ConnectionPool::default(),
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    async fn connect(&self, host: &str, addr: &str) -> Result<Connection> {
+        let proxy = self.config.proxy.clone().or_else(Proxy::from_env);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(proxy = proxy.as_ref().map(|p| p.addr.as_str()), "connecting");
+        let stream = match proxy {
+            Some(proxy) => {
+                let mut stream = connect_tcp(&proxy.addr, self.config.local_address).await?;
+                proxy.tunnel(&mut stream, addr).await?;
+                stream
+            }
+            None => connect_tcp(addr, self.config.local_address).await?,
+        };
+        let tls = tls_connect(host, stream, &self.config.root_certs).await?;
+        #[cfg(feature = "tracing")]
+        tracing::trace!("TLS handshake complete");
+        let conn = Connection::handshake(tls).await?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!("connection established");
+        Ok(conn)
+    }
+
+    async fn send_request(&self, request: Request<StreamingBody>) -> Result<Response<Bytes>> {
+        let (host, addr) = connect_target(request.uri())?;
+
+        let mut pooled = self.pool.checkout(&addr, self.config.connection_lifetime);
+        if let Some((ref mut conn, _)) = pooled
+            && conn.sender.ready().await.is_err()
+        {
+            pooled = None;
+        }
+        let (mut conn, established) = match pooled {
+            Some(pooled) => {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(addr = %addr, "reusing pooled connection");
+                pooled
+            }
+            None => (self.connect(&host, &addr).await?, Instant::now()),
+        };
+
+        let resp = conn.send(request).await?;
+
+        if !conn.is_closed() {
+            self.pool.checkin(addr, conn, established);
+        }
+
+        Ok(resp)
+    }
+}
+
+impl Transport for TlsTransport {
+    fn send(&self, request: Request<Bytes>) -> TransportFuture<'_> {
+        Box::pin(self.send_request(request.map(full_body)))
+    }
+
+    fn send_streaming(&self, request: Request<StreamingBody>) -> TransportFuture<'_> {
+        Box::pin(self.send_request(request))
+    }
+}
@@ -1,48 +1,357 @@
 use crate::error::{Result, WebhookError};
-use crate::multipart::MultipartBuilder;
-use crate::models::{Attachment, MessageBuilder, WebhookResponse};
-use http_body_util::{BodyExt, Full};
+use crate::history::{History, SendRecord};
+use crate::limiter::RateLimiter;
+use crate::metrics::{ErrorHook, MetricEvent, MetricsHook};
+use crate::multipart::{validate_boundary_prefix, MultipartBuilder, DEFAULT_MAX_UPLOAD_SIZE};
+use crate::models::{
+    Attachment, AttachmentMetadata, EditableMessage, Embed, MessageBuilder, WebhookInfo, WebhookResponse, MAX_ATTACHMENTS, MAX_CONTENT_LENGTH, MAX_EMBEDS,
+    MAX_USERNAME_LENGTH, split_content,
+};
+use crate::retry::RetryPolicy;
+use crate::transport::{Proxy, StreamingBody, TlsTransport, Transport, TransportConfig, TransportFuture};
+use futures_util::StreamExt;
 use hyper::body::Bytes;
-use hyper::client::conn::http1::handshake;
-use hyper::http::{header, Method, Request, Uri};
-use hyper_util::rt::TokioIo;
-use tokio::net::TcpStream;
-use tokio_native_tls::TlsConnector;
-use native_tls::TlsConnector as NativeTlsConnector;
+use hyper::http::{header, HeaderMap, Method, Request, Response, StatusCode, Uri};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 const USER_AGENT: &str = "rs-hook (https://github.com/rs-hook, 0.1.0)";
+const FORUM_THREAD_REQUIRED_CODE: u32 = 220001;
+const DEFAULT_DIAGNOSTIC_HEADERS: &[&str] = &["cf-ray", "via", "x-trace-id"];
+const AUDIT_LOG_REASON_HEADER: &str = "x-audit-log-reason";
+const MAX_AUDIT_REASON_LENGTH: usize = 512;
 
-fn tls_error<E: std::fmt::Display>(err: E) -> WebhookError {
-    WebhookError::TokioTls(err.to_string())
+#[derive(Debug, Default)]
+struct LiveState {
+    info: Option<WebhookInfo>,
+    forum: bool,
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    #[serde(flatten)]
+    message: &'a MessageBuilder,
+    attachments: &'a [AttachmentMetadata],
 }
 
 #[derive(Debug, Clone)]
 pub struct Webhook {
     url: String,
     timeout: Option<u64>,
+    boundary_prefix: Option<String>,
+    dedup_attachments: bool,
+    max_upload_size: u64,
+    streaming_uploads: bool,
+    ascii_json: bool,
+    diagnostic_headers: Vec<String>,
+    retry: Option<RetryPolicy>,
+    metrics: Option<MetricsHook>,
+    error_hook: Option<ErrorHook>,
+    history: Option<History>,
+    thread_id: Option<String>,
+    audit_reason: Option<String>,
+    user_agent: Option<String>,
+    max_redirects: u32,
+    limiter: Option<RateLimiter>,
+    live: Arc<Mutex<LiveState>>,
+    tls: Arc<TlsTransport>,
+    transport: Option<Arc<dyn Transport>>,
 }
 
 impl Webhook {
     pub fn new(url: impl Into<String>) -> Result<Self> {
         let url = url.into();
-        if !url.contains("discord.com/api/webhooks/")
-            && !url.contains("discordapp.com/api/webhooks/")
-        {
+        if !is_discord_webhook_url(&url) {
+            return Err(WebhookError::InvalidUrl);
+        }
+        let (id, token) = webhook_parts(&url).ok_or(WebhookError::InvalidUrl)?;
+        if id.is_empty() || !url[id].bytes().all(|b| b.is_ascii_digit()) || token.is_empty() {
             return Err(WebhookError::InvalidUrl);
         }
         Ok(Self {
             url,
             timeout: None,
+            boundary_prefix: None,
+            dedup_attachments: false,
+            max_upload_size: DEFAULT_MAX_UPLOAD_SIZE,
+            streaming_uploads: false,
+            ascii_json: false,
+            diagnostic_headers: Vec::new(),
+            retry: None,
+            metrics: None,
+            error_hook: None,
+            history: None,
+            thread_id: None,
+            audit_reason: None,
+            user_agent: None,
+            max_redirects: 0,
+            limiter: None,
+            live: Arc::default(),
+            tls: Arc::new(TlsTransport::default()),
+            transport: None,
         })
     }
 
+    pub fn rotate_token(&mut self, new_token: &str) -> Result<()> {
+        if new_token.is_empty() || new_token.contains(['/', '?', '#']) {
+            return Err(WebhookError::InvalidUrl);
+        }
+        let (_, token) = webhook_parts(&self.url).ok_or(WebhookError::InvalidUrl)?;
+        self.url.replace_range(token, new_token);
+        Ok(())
+    }
+
+    pub fn id(&self) -> &str {
+        webhook_parts(&self.url).map_or("", |(id, _)| &self.url[id])
+    }
+
+    pub fn token(&self) -> &str {
+        webhook_parts(&self.url).map_or("", |(_, token)| &self.url[token])
+    }
+
     pub fn with_timeout(mut self, seconds: u64) -> Self {
         self.timeout = Some(seconds);
         self
     }
 
+    pub fn in_thread(mut self, thread_id: impl Into<String>) -> Self {
+        self.thread_id = Some(thread_id.into());
+        self
+    }
+
+    /// Sends `reason` as `X-Audit-Log-Reason` on edit, delete and fetch requests.
+    pub fn with_audit_reason(mut self, reason: impl Into<String>) -> Result<Self> {
+        let reason = reason.into();
+        let len = reason.chars().count();
+        if len > MAX_AUDIT_REASON_LENGTH {
+            return Err(WebhookError::Request(format!(
+                "Audit log reason too long: {} characters (max {})",
+                len, MAX_AUDIT_REASON_LENGTH
+            )));
+        }
+        self.audit_reason = Some(percent_encode(&reason));
+        Ok(self)
+    }
+
+    /// Replaces the default `User-Agent` so Discord can tell your application apart.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Follows up to `max` 301/302/307/308 redirects to https Discord hosts, repeating the
+    /// request with the same method and body. Other redirects come back as `Status` errors,
+    /// and streamed uploads are never redirected. Disabled (0) by default.
+    pub fn with_max_redirects(mut self, max: u32) -> Self {
+        self.max_redirects = max;
+        self
+    }
+
+    /// Paces every request, retries included, through `limiter`. Clones of this webhook
+    /// share it, and a 429's retry-after holds back all of them.
+    pub fn with_shared_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Retries 429s after their retry-after and 5xx responses with exponential backoff and
+    /// jitter, making at most `max_attempts` attempts. Other 4xx responses fail immediately.
+    /// There is no overall time limit, so a long retry-after is always waited out; use
+    /// `send_retrying` with `RetryPolicy::max_elapsed` to cap it.
+    pub fn with_retry(mut self, max_attempts: u32) -> Self {
+        self.retry = Some(RetryPolicy::status_only(max_attempts));
+        self
+    }
+
+    pub fn with_metrics_hook(mut self, hook: impl Fn(MetricEvent) + Send + Sync + 'static) -> Self {
+        self.metrics = Some(MetricsHook::new(hook));
+        self
+    }
+
+    pub fn with_max_upload_size(mut self, bytes: u64) -> Self {
+        self.max_upload_size = bytes;
+        self
+    }
+
+    /// Reads attachment files from disk in chunks while the request is sent instead of
+    /// buffering them in memory first.
+    pub fn with_streaming_uploads(mut self, enabled: bool) -> Self {
+        self.streaming_uploads = enabled;
+        self
+    }
+
+    pub fn with_ascii_json(mut self) -> Self {
+        self.ascii_json = true;
+        self
+    }
+
+    pub fn with_diagnostic_header(mut self, name: impl Into<String>) -> Self {
+        self.diagnostic_headers.push(name.into());
+        self
+    }
+
+    pub fn with_error_hook(mut self, hook: impl Fn(&WebhookError) + Send + Sync + 'static) -> Self {
+        self.error_hook = Some(ErrorHook::new(hook));
+        self
+    }
+
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.history = Some(History::new(capacity));
+        self
+    }
+
+    pub fn recent_history(&self) -> Vec<SendRecord> {
+        self.history.as_ref().map(History::snapshot).unwrap_or_default()
+    }
+
+    pub fn with_multipart_boundary_prefix(mut self, prefix: impl Into<String>) -> Result<Self> {
+        let prefix = prefix.into();
+        validate_boundary_prefix(&prefix)?;
+        self.boundary_prefix = Some(prefix);
+        Ok(self)
+    }
+
+    pub fn with_attachment_dedup(mut self, dedup: bool) -> Self {
+        self.dedup_attachments = dedup;
+        self
+    }
+
+    pub fn with_connection_lifetime(mut self, lifetime: Duration) -> Self {
+        self.configure_tls(|config| config.connection_lifetime = lifetime);
+        self
+    }
+
+    pub fn with_outbound_interface(mut self, addr: IpAddr) -> Self {
+        self.configure_tls(|config| config.local_address = Some(addr));
+        self
+    }
+
+    pub fn with_proxy(mut self, url: impl Into<String>) -> Result<Self> {
+        let proxy = Proxy::parse(&url.into())?;
+        self.configure_tls(|config| config.proxy = Some(proxy));
+        Ok(self)
+    }
+
+    /// Trusts an extra root certificate (PEM) in addition to the default roots, e.g. a
+    /// corporate CA that re-signs TLS traffic. Unparseable input fails with `InvalidCertificate`
+    /// whichever TLS backend is enabled.
+    pub fn with_root_cert(mut self, pem: &[u8]) -> Result<Self> {
+        crate::transport::check_root_cert(pem)?;
+        self.configure_tls(|config| config.root_certs.push(pem.to_vec()));
+        Ok(self)
+    }
+
+    fn configure_tls(&mut self, f: impl FnOnce(&mut TransportConfig)) {
+        let mut config = self.tls.config.clone();
+        f(&mut config);
+        self.tls = Arc::new(TlsTransport::new(config));
+    }
+
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    fn emit(&self, event: MetricEvent) {
+        if let Some(ref metrics) = self.metrics {
+            metrics.emit(event);
+        }
+    }
+
+    fn on_retry(&self, attempt: u32, delay: Duration) {
+        self.emit(MetricEvent::RetryScheduled { attempt, delay });
+    }
+
+    fn transport(&self) -> &dyn Transport {
+        match self.transport {
+            Some(ref transport) => transport.as_ref(),
+            None => self.tls.as_ref(),
+        }
+    }
+
     pub async fn send(&self, message: MessageBuilder) -> Result<WebhookResponse> {
-        self.send_internal(message, None).await
+        self.send_internal(message, None, false).await
+    }
+
+    pub async fn send_as(
+        &self,
+        message: MessageBuilder,
+        username: &str,
+        avatar_url: Option<&str>,
+    ) -> Result<WebhookResponse> {
+        crate::models::check_length("username", username, MAX_USERNAME_LENGTH)?;
+        let mut message = message.username(username);
+        if let Some(avatar_url) = avatar_url {
+            message = message.avatar_url(avatar_url);
+        }
+        self.send(message).await
+    }
+
+    pub async fn send_embed(&self, embed: impl Into<Embed>) -> Result<WebhookResponse> {
+        self.send(MessageBuilder::new().embed(embed)).await
+    }
+
+    pub async fn send_embeds(&self, embeds: Vec<Embed>) -> Result<WebhookResponse> {
+        if embeds.len() > MAX_EMBEDS {
+            return Err(WebhookError::Request(format!(
+                "Too many embeds ({}, max {}); use send_embeds_paged",
+                embeds.len(),
+                MAX_EMBEDS
+            )));
+        }
+        self.send(MessageBuilder::new().embeds(embeds)).await
+    }
+
+    /// Sends `embeds` in messages of up to 10 embeds each, stopping at the first failure.
+    pub async fn send_embeds_paged(&self, embeds: Vec<Embed>) -> Result<Vec<WebhookResponse>> {
+        let mut responses = Vec::with_capacity(embeds.len().div_ceil(MAX_EMBEDS));
+        for page in embeds.chunks(MAX_EMBEDS) {
+            responses.push(self.send_embeds(page.to_vec()).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Sends every message with at most `concurrency` requests in flight. Results keep the
+    /// input order, and a failed send (a 429 included) only fills its own slot.
+    pub async fn send_many(&self, messages: Vec<MessageBuilder>, concurrency: usize) -> Vec<Result<WebhookResponse>> {
+        futures_util::stream::iter(messages)
+            .map(|message| self.send(message))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Races the send against `token`. If the token fires first the in-flight request is
+    /// dropped, which closes its connection instead of returning it to the pool.
+    pub async fn send_cancellable(&self, message: MessageBuilder, token: CancellationToken) -> Result<WebhookResponse> {
+        tokio::select! {
+            result = self.send(message) => result,
+            _ = token.cancelled() => Err(WebhookError::Cancelled),
+        }
+    }
+
+    /// Sends the message and swallows any error, reporting it to the error hook. Without a
+    /// hook the error is logged through `tracing` when that feature is enabled and dropped
+    /// otherwise; a library should not write to stderr. Returns whether the send succeeded.
+    pub async fn send_or_log(&self, message: MessageBuilder) -> bool {
+        match self.send(message).await {
+            Ok(_) => true,
+            Err(err) => {
+                match self.error_hook {
+                    Some(ref hook) => hook.report(&err),
+                    #[cfg(feature = "tracing")]
+                    None => tracing::error!(error = %err, "webhook send failed"),
+                    #[cfg(not(feature = "tracing"))]
+                    None => {}
+                }
+                false
+            }
+        }
     }
 
     pub async fn send_with_attachments(
@@ -50,76 +359,436 @@ impl Webhook {
         message: MessageBuilder,
         attachments: Vec<Attachment>,
     ) -> Result<WebhookResponse> {
-        self.send_internal(message, Some(attachments)).await
+        self.send_internal(message, Some(attachments), false).await
+    }
+
+    /// Sends `message` with an explicit `wait` flag. With `wait = false` Discord answers
+    /// 204 with an empty body; with `wait = true` it answers 200 with the created message,
+    /// which `WebhookResponse::parse_message` turns into a `SentMessage`.
+    pub async fn send_wait(&self, message: MessageBuilder, wait: bool) -> Result<WebhookResponse> {
+        self.send_internal(message, None, wait).await
+    }
+
+    pub async fn send_with_attachments_wait(
+        &self,
+        message: MessageBuilder,
+        attachments: Vec<Attachment>,
+    ) -> Result<WebhookResponse> {
+        self.send_internal(message, Some(attachments), true).await
+    }
+
+    /// Validates every message up front, then sends them in order. If a send fails,
+    /// the messages already delivered are deleted again; this rollback is best-effort.
+    pub async fn send_all_or_nothing(&self, messages: Vec<MessageBuilder>) -> Result<Vec<WebhookResponse>> {
+        let messages = messages
+            .into_iter()
+            .map(|message| self.prepare(message, None))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut responses = Vec::with_capacity(messages.len());
+        let mut sent_ids: Vec<String> = Vec::with_capacity(messages.len());
+        for message in messages {
+            let response = match self.send_internal(message, None, true).await {
+                Ok(response) => response,
+                Err(err) => {
+                    for id in sent_ids.iter().rev() {
+                        let _ = self.delete_message(id).await;
+                    }
+                    return Err(err);
+                }
+            };
+            if let Ok(sent) = response.parse_message() {
+                sent_ids.push(sent.id);
+            }
+            responses.push(response);
+        }
+        Ok(responses)
+    }
+
+    pub async fn send_respecting_rate_limit(&self, message: MessageBuilder) -> Result<WebhookResponse> {
+        match self.send(message.clone()).await {
+            Err(err @ WebhookError::Status { status, .. }) if status == StatusCode::TOO_MANY_REQUESTS => {
+                tokio::time::sleep(err.retry_after().unwrap_or_default()).await;
+                self.send(message).await
+            }
+            result => result,
+        }
+    }
+
+    pub async fn send_long(&self, short_summary: &str, full_text: &str) -> Result<WebhookResponse> {
+        if full_text.chars().count() <= MAX_CONTENT_LENGTH {
+            return self.send(MessageBuilder::new().content(full_text)).await;
+        }
+
+        let attachment = Attachment::from_bytes("output.txt", full_text.as_bytes());
+        self.send_with_attachments(MessageBuilder::new().content(short_summary), vec![attachment])
+            .await
+    }
+
+    /// Sends `content` as consecutive messages of at most 2000 characters, split at line
+    /// boundaries where possible. Stops at the first failure.
+    pub async fn send_chunked(&self, content: &str) -> Result<Vec<WebhookResponse>> {
+        let mut responses = Vec::new();
+        for chunk in split_content(content, MAX_CONTENT_LENGTH) {
+            responses.push(self.send(MessageBuilder::new().content(chunk)).await?);
+        }
+        Ok(responses)
+    }
+
+    pub async fn send_retrying(&self, message: MessageBuilder, policy: RetryPolicy) -> Result<WebhookResponse> {
+        self.send_with_policy(message, None, false, Some(&policy)).await
     }
 
     async fn send_internal(
         &self,
         message: MessageBuilder,
         attachments: Option<Vec<Attachment>>,
+        wait: bool,
     ) -> Result<WebhookResponse> {
-        let message = message.build()?;
-
-        let uri: Uri = self.url.parse().map_err(|_| WebhookError::InvalidUrl)?;
+        self.send_with_policy(message, attachments, wait, self.retry.as_ref())
+            .await
+    }
 
-        let (body, content_type) = if let Some(attachments) = attachments {
-            let mut builder = MultipartBuilder::new();
-            builder = builder.add_json("payload_json".to_string(), &message)?;
+    async fn send_with_policy(
+        &self,
+        message: MessageBuilder,
+        attachments: Option<Vec<Attachment>>,
+        wait: bool,
+        policy: Option<&RetryPolicy>,
+    ) -> Result<WebhookResponse> {
+        let started = Instant::now();
+        let result = self.send_prepared(message, attachments, wait, policy).await;
+        self.learn_from(&result);
+        self.record(started, &result);
+        result
+    }
 
-            for (idx, attachment) in attachments.into_iter().enumerate() {
-                builder = builder.add_attachment(idx, attachment)?;
+    async fn send_prepared(
+        &self,
+        message: MessageBuilder,
+        attachments: Option<Vec<Attachment>>,
+        wait: bool,
+        policy: Option<&RetryPolicy>,
+    ) -> Result<WebhookResponse> {
+        let message = self.prepare(message, attachments.as_deref())?;
+        match policy {
+            Some(policy) => {
+                policy
+                    .run(
+                        || self.send_once(message.clone(), attachments.clone(), wait),
+                        |attempt, delay| self.on_retry(attempt, delay),
+                    )
+                    .await
             }
+            None => self.send_once(message, attachments, wait).await,
+        }
+    }
 
-            builder.build()?
+    /// Fetches the webhook object once to check that the token is valid and caches it.
+    /// Discord does not expose the channel type to webhook tokens, so forum channels are
+    /// recognised from the first "thread_name or thread_id required" rejection; after
+    /// that, thread-less sends fail locally instead of reaching Discord.
+    pub async fn validate_live(&self) -> Result<()> {
+        if self.live.lock().unwrap().info.is_some() {
+            return Ok(());
+        }
+        let base = self.url.split('?').next().unwrap_or(&self.url);
+        let uri = base.parse().map_err(|_| WebhookError::InvalidUrl)?;
+        let resp = self.parse_response(self.dispatch(Method::GET, uri, None, Vec::new()).await?)?;
+        let info: WebhookInfo = serde_json::from_str(&resp.body)
+            .map_err(|e| WebhookError::InvalidResponse(format!("not a webhook object: {}", e)))?;
+        self.live.lock().unwrap().info = Some(info);
+        Ok(())
+    }
+
+    /// Fetches the webhook object without posting anything. Returns `Ok(false)` if Discord
+    /// answers 404 (the webhook was deleted) and an error if it cannot be reached.
+    pub async fn ping(&self) -> Result<bool> {
+        let base = self.url.split('?').next().unwrap_or(&self.url);
+        let uri = base.parse().map_err(|_| WebhookError::InvalidUrl)?;
+        let resp = self.dispatch(Method::GET, uri, None, Vec::new()).await?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        self.parse_response(resp).map(|_| true)
+    }
+
+    /// Changes the webhook's avatar to `image_bytes` (sent as a base64 data URI), or
+    /// clears it when `image_bytes` is empty.
+    pub async fn set_avatar(&self, image_bytes: &[u8], mime: &str) -> Result<WebhookResponse> {
+        use base64::Engine;
+
+        let avatar = if image_bytes.is_empty() {
+            serde_json::Value::Null
         } else {
-            let json = serde_json::to_string(&message)?;
-            (json.into_bytes(), "application/json".to_string())
+            if !mime.starts_with("image/") {
+                return Err(WebhookError::Request(format!("Avatar must be an image, got {}", mime)));
+            }
+            let encoded = base64::engine::general_purpose::STANDARD.encode(image_bytes);
+            format!("data:{};base64,{}", mime, encoded).into()
         };
+        let body = serde_json::to_vec(&serde_json::json!({ "avatar": avatar }))?;
+        let base = self.url.split('?').next().unwrap_or(&self.url);
+        let uri = base.parse().map_err(|_| WebhookError::InvalidUrl)?;
+        let resp = self
+            .dispatch(Method::PATCH, uri, Some("application/json".to_string()), body)
+            .await?;
+        self.parse_response(resp)
+    }
 
-        let host = uri
-            .host()
-            .ok_or_else(|| WebhookError::Request("Missing host".to_string()))?
-            .to_string();
+    pub fn webhook_info(&self) -> Option<WebhookInfo> {
+        self.live.lock().unwrap().info.clone()
+    }
 
-        let port = uri.port_u16().unwrap_or(443);
-        let addr = format!("{}:{}", host, port);
+    pub fn is_forum_channel(&self) -> bool {
+        self.live.lock().unwrap().forum
+    }
 
-        let stream = TcpStream::connect(&addr).await?;
-        let tls_connector = NativeTlsConnector::new()?;
-        let connector = TlsConnector::from(tls_connector);
-        let tls_stream = connector
-            .connect(&host, stream)
-            .await
-            .map_err(tls_error)?;
-        let io = TokioIo::new(tls_stream);
+    fn learn_from(&self, result: &Result<WebhookResponse>) {
+        if let Err(err) = result
+            && err.api_error().is_some_and(|api| api.code == FORUM_THREAD_REQUIRED_CODE)
+        {
+            self.live.lock().unwrap().forum = true;
+        }
+    }
 
-        let (mut sender, conn) = handshake(io).await?;
-        tokio::spawn(async move {
-            if let Err(err) = conn.await {
-                eprintln!("Connection error: {:?}", err);
+    fn record(&self, started: Instant, result: &Result<WebhookResponse>) {
+        let Some(ref history) = self.history else {
+            return;
+        };
+        let (status, error) = match result {
+            Ok(response) => (Some(response.status_code), None),
+            Err(WebhookError::Status { status, .. }) => {
+                (Some(status.as_u16()), Some(format!("HTTP status error: {}", status)))
+            }
+            Err(err) => {
+                let mut error = err.to_string().replace(&self.url, "[redacted]");
+                if !self.token().is_empty() {
+                    error = error.replace(self.token(), "[redacted]");
+                }
+                (None, Some(error))
             }
+        };
+        history.push(SendRecord {
+            timestamp: std::time::SystemTime::now(),
+            status,
+            latency: started.elapsed(),
+            error,
         });
+    }
 
-        let full_body = Full::new(Bytes::from(body));
-        let req = Request::builder()
-            .uri(uri)
-            .method(Method::POST)
-            .header(header::USER_AGENT, USER_AGENT)
-            .header(header::HOST, host)
-            .header(header::CONTENT_TYPE, content_type)
-            .body(full_body)
-            .map_err(|e| WebhookError::Request(e.to_string()))?;
+    /// Runs every local check a send makes before touching the network.
+    fn prepare(&self, message: MessageBuilder, attachments: Option<&[Attachment]>) -> Result<MessageBuilder> {
+        let message = message.build()?;
+        if message.is_empty() && attachments.is_none_or(<[Attachment]>::is_empty) {
+            return Err(WebhookError::EmptyMessage);
+        }
+        message.check_attachment_references(attachments.unwrap_or_default())?;
+        if self.thread_id.is_some() && message.thread_name.is_some() {
+            return Err(WebhookError::Request(
+                "thread_name cannot be combined with thread_id".to_string(),
+            ));
+        }
+        if self.is_forum_channel() && self.thread_id.is_none() && message.thread_name.is_none() {
+            return Err(WebhookError::Request(
+                "Forum webhooks require a thread_name or thread_id".to_string(),
+            ));
+        }
+        Ok(message)
+    }
+
+    async fn send_once(
+        &self,
+        message: MessageBuilder,
+        attachments: Option<Vec<Attachment>>,
+        wait: bool,
+    ) -> Result<WebhookResponse> {
+        let uri = self.execute_uri(wait)?;
+
+        let attachments = attachments.filter(|attachments| message.requires_multipart(attachments));
+        let (body, content_type) = match attachments {
+            Some(attachments) if self.streaming_uploads => {
+                let (body, content_type, len) = self
+                    .multipart_body(&message, attachments, true)
+                    .await?
+                    .build_streaming()?;
+                let resp = self.dispatch_streaming(uri, content_type, body, len).await?;
+                return self.parse_response(resp);
+            }
+            attachments => self.encode_body(&message, attachments).await?,
+        };
+
+        let resp = self
+            .dispatch(Method::POST, uri, Some(content_type), body)
+            .await?;
+
+        self.parse_response(resp)
+    }
+
+    /// Returns the URL, body and content type that `send_with_attachments` would POST,
+    /// without opening a connection.
+    pub async fn build_request(
+        &self,
+        message: MessageBuilder,
+        attachments: Option<Vec<Attachment>>,
+    ) -> Result<(String, Vec<u8>, String)> {
+        let message = self.prepare(message, attachments.as_deref())?;
+        let uri = self.execute_uri(false)?;
+        let attachments = attachments.filter(|attachments| message.requires_multipart(attachments));
+        let (body, content_type) = self.encode_body(&message, attachments).await?;
+        Ok((uri.to_string(), body, content_type))
+    }
+
+    async fn encode_body(
+        &self,
+        message: &MessageBuilder,
+        attachments: Option<Vec<Attachment>>,
+    ) -> Result<(Vec<u8>, String)> {
+        match attachments {
+            Some(attachments) => self.multipart_body(message, attachments, false).await?.build(),
+            None => {
+                let json = crate::json::to_vec(message, self.ascii_json)?;
+                Ok((json, "application/json".to_string()))
+            }
+        }
+    }
+
+    async fn multipart_body(
+        &self,
+        message: &MessageBuilder,
+        mut attachments: Vec<Attachment>,
+        streaming: bool,
+    ) -> Result<MultipartBuilder> {
+        if self.dedup_attachments {
+            let mut seen = HashSet::new();
+            attachments.retain(|attachment| seen.insert(attachment.clone()));
+        }
+        if attachments.len() > MAX_ATTACHMENTS {
+            return Err(WebhookError::TooManyAttachments(attachments.len()));
+        }
+
+        let mut builder = MultipartBuilder::new()
+            .with_max_upload_size(self.max_upload_size)
+            .with_streaming(streaming);
+        if let Some(ref prefix) = self.boundary_prefix {
+            builder = builder.with_boundary_prefix(prefix.as_str())?;
+        }
+        let mut ids = HashSet::new();
+        let mut metadata = Vec::with_capacity(attachments.len());
+        for (idx, attachment) in attachments.iter().enumerate() {
+            let id = attachment.id.unwrap_or(idx as u64);
+            if !ids.insert(id) {
+                return Err(WebhookError::Request(format!("Duplicate attachment id {}", id)));
+            }
+            metadata.push(AttachmentMetadata {
+                id,
+                filename: attachment.filename(),
+                description: attachment.description.clone(),
+            });
+        }
+
+        let payload = Payload {
+            message,
+            attachments: &metadata,
+        };
+        builder = builder.add_json("payload_json".to_string(), &payload, self.ascii_json)?;
+
+        for (attachment, meta) in attachments.into_iter().zip(&metadata) {
+            builder = builder.add_attachment(meta.id, attachment).await?;
+        }
+        Ok(builder)
+    }
+
+    pub async fn edit_message(&self, message_id: &str, message: MessageBuilder) -> Result<WebhookResponse> {
+        let message = message.build()?;
+        let uri = self.message_uri(message_id)?;
+        let body = crate::json::to_vec(&message, self.ascii_json)?;
+
+        let resp = self
+            .dispatch(Method::PATCH, uri, Some("application/json".to_string()), body)
+            .await?;
+
+        self.parse_response(resp)
+    }
+
+    pub async fn execute(&self, wait: bool) -> Result<WebhookResponse> {
+        let uri = self.execute_uri(wait)?;
+
+        let resp = self
+            .dispatch(
+                Method::POST,
+                uri,
+                Some("application/json".to_string()),
+                b"{}".to_vec(),
+            )
+            .await?;
+
+        self.parse_response(resp)
+    }
+
+    pub async fn get_message(&self, message_id: &str) -> Result<WebhookResponse> {
+        let uri = self.message_uri(message_id)?;
+        let resp = self.dispatch(Method::GET, uri, None, Vec::new()).await?;
+        self.parse_response(resp)
+    }
+
+    /// Fetches the current message, applies `f` to a builder populated from it and
+    /// PATCHes the result. Only content, rich embeds, tts, supported components and the suppress-embeds
+    /// flag are carried over; attachments are left untouched by Discord, but other fields
+    /// are not restored.
+    pub async fn edit_message_merge(
+        &self,
+        message_id: &str,
+        f: impl FnOnce(&mut MessageBuilder),
+    ) -> Result<WebhookResponse> {
+        let current = self.get_message(message_id).await?;
+        let mut message = serde_json::from_str::<EditableMessage>(&current.body)
+            .map_err(|e| WebhookError::InvalidResponse(format!("not a message object: {}", e)))?
+            .into_builder()?;
+        f(&mut message);
+        self.edit_message(message_id, message).await
+    }
+
+    pub async fn delete_message(&self, message_id: &str) -> Result<()> {
+        let uri = self.message_uri(message_id)?;
+        let resp = self.dispatch(Method::DELETE, uri, None, Vec::new()).await?;
+        self.parse_response(resp)?;
+        Ok(())
+    }
+
+    fn execute_uri(&self, wait: bool) -> Result<Uri> {
+        let mut url = self.url.clone();
+        if wait {
+            append_query(&mut url, "wait", "true");
+        }
+        if let Some(ref thread_id) = self.thread_id {
+            append_query(&mut url, "thread_id", thread_id);
+        }
+        url.parse().map_err(|_| WebhookError::InvalidUrl)
+    }
 
-        let resp = sender.send_request(req).await?;
+    fn message_uri(&self, message_id: &str) -> Result<Uri> {
+        if message_id.is_empty() || !message_id.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(WebhookError::Request(format!("Invalid message id: {:?}", message_id)));
+        }
+        let base = self.url.split('?').next().unwrap_or(&self.url);
+        let mut url = format!("{}/messages/{}", base, message_id);
+        if let Some(ref thread_id) = self.thread_id {
+            append_query(&mut url, "thread_id", thread_id);
+        }
+        url.parse().map_err(|_| WebhookError::InvalidUrl)
+    }
 
+    /// Turns every raw response into a `WebhookResponse` (headers and diagnostics included)
+    /// or a `Status` error carrying the parsed retry-after.
+    fn parse_response(&self, resp: Response<Bytes>) -> Result<WebhookResponse> {
         let status = resp.status();
-        let body = resp.into_body();
-        let body_bytes = body.collect().await?.to_bytes();
-        let body_string = String::from_utf8_lossy(&body_bytes).to_string();
+        let body_string = String::from_utf8_lossy(resp.body()).to_string();
 
         if !status.is_success() {
             return Err(WebhookError::Status {
                 status,
+                retry_after: parse_retry_after(status, resp.headers(), &body_string),
                 body: body_string,
             });
         }
@@ -127,62 +796,296 @@ impl Webhook {
         Ok(WebhookResponse {
             status_code: status.as_u16(),
             body: body_string,
+            diagnostics: self.diagnostics(resp.headers()),
+            headers: collect_headers(resp.headers()),
         })
     }
 
-    pub async fn execute(&self, wait: bool) -> Result<WebhookResponse> {
-        let url = if wait {
-            format!("{}?wait=true", self.url)
-        } else {
-            self.url.clone()
-        };
+    fn diagnostics(&self, headers: &HeaderMap) -> HashMap<String, String> {
+        DEFAULT_DIAGNOSTIC_HEADERS
+            .iter()
+            .copied()
+            .chain(self.diagnostic_headers.iter().map(String::as_str))
+            .filter_map(|name| {
+                let value = headers.get(name)?.to_str().ok()?;
+                Some((name.to_ascii_lowercase(), value.to_string()))
+            })
+            .collect()
+    }
+
+    async fn dispatch(
+        &self,
+        method: Method,
+        mut uri: Uri,
+        content_type: Option<String>,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>> {
+        let body = Bytes::from(body);
+        let mut redirects = 0;
+        loop {
+            let mut builder = self.request_builder(method.clone(), uri.clone(), content_type.clone())?;
+            if !body.is_empty() {
+                builder = builder.header(header::CONTENT_LENGTH, body.len());
+            }
+            let req = builder
+                .body(body.clone())
+                .map_err(|e| WebhookError::Request(e.to_string()))?;
+            let resp = self.observe(|| self.transport().send(req)).await?;
 
-        let uri: Uri = url.parse().map_err(|_| WebhookError::InvalidUrl)?;
+            // 301/302 are re-issued with the same method and body as well: switching to GET
+            // would fetch the webhook object instead of posting. 303 still surfaces as an error.
+            let followed = matches!(resp.status().as_u16(), 301 | 302 | 307 | 308);
+            if !followed || redirects >= self.max_redirects {
+                return Ok(resp);
+            }
+            let Some(location) = resp.headers().get(header::LOCATION) else {
+                return Ok(resp);
+            };
+            uri = resolve_redirect(&uri, location.to_str().unwrap_or_default())?;
+            redirects += 1;
+        }
+    }
+
+    async fn dispatch_streaming(
+        &self,
+        uri: Uri,
+        content_type: String,
+        body: StreamingBody,
+        content_length: u64,
+    ) -> Result<Response<Bytes>> {
+        let req = self
+            .request_builder(Method::POST, uri, Some(content_type))?
+            .header(header::CONTENT_LENGTH, content_length)
+            .body(body)
+            .map_err(|e| WebhookError::Request(e.to_string()))?;
+        self.observe(|| self.transport().send_streaming(req)).await
+    }
 
+    fn request_builder(
+        &self,
+        method: Method,
+        uri: Uri,
+        content_type: Option<String>,
+    ) -> Result<hyper::http::request::Builder> {
         let host = uri
             .host()
-            .ok_or_else(|| WebhookError::Request("Missing host".to_string()))?
-            .to_string();
+            .ok_or_else(|| WebhookError::Request("Missing host".to_string()))?;
+        let host = match uri.port_u16() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
 
-        let port = uri.port_u16().unwrap_or(443);
-        let addr = format!("{}:{}", host, port);
+        // Executing the webhook is not an audited action; everything else is.
+        let audited = method != Method::POST;
+        let mut builder = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header(header::USER_AGENT, self.user_agent.as_deref().unwrap_or(USER_AGENT))
+            .header(header::HOST, host)
+            .header(header::CONNECTION, "keep-alive");
+        if let Some(content_type) = content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+        if audited && let Some(ref reason) = self.audit_reason {
+            builder = builder.header(AUDIT_LOG_REASON_HEADER, reason);
+        }
+        #[cfg(feature = "gzip")]
+        {
+            builder = builder.header(header::ACCEPT_ENCODING, "gzip");
+        }
+        Ok(builder)
+    }
 
-        let stream = TcpStream::connect(&addr).await?;
-        let tls_connector = NativeTlsConnector::new()?;
-        let connector = TlsConnector::from(tls_connector);
-        let tls_stream = connector
-            .connect(&host, stream)
-            .await
-            .map_err(tls_error)?;
-        let io = TokioIo::new(tls_stream);
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(webhook_id = self.id())))]
+    async fn observe<'a>(&'a self, send: impl FnOnce() -> TransportFuture<'a>) -> Result<Response<Bytes>> {
+        if let Some(ref limiter) = self.limiter {
+            limiter.acquire().await;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!("sending request");
+        self.emit(MetricEvent::RequestStarted);
+        let started = Instant::now();
+        let send = send();
+        let resp = match self.timeout {
+            Some(seconds) => tokio::time::timeout(Duration::from_secs(seconds), send)
+                .await
+                .map_err(|_| WebhookError::Timeout(seconds))??,
+            None => send.await?,
+        };
+        #[cfg(feature = "gzip")]
+        let resp = decode_gzip(resp)?;
 
-        let (mut sender, conn) = handshake(io).await?;
-        tokio::spawn(async move {
-            if let Err(err) = conn.await {
-                eprintln!("Connection error: {:?}", err);
-            }
+        let status = resp.status();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            status = status.as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            rate_limit_remaining = resp.headers().get("x-ratelimit-remaining").and_then(|v| v.to_str().ok()),
+            "received response"
+        );
+        self.emit(MetricEvent::RequestCompleted {
+            status: status.as_u16(),
+            latency: started.elapsed(),
         });
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let body = String::from_utf8_lossy(resp.body());
+            let retry_after = parse_retry_after(status, resp.headers(), &body);
+            if let (Some(limiter), Some(delay)) = (&self.limiter, retry_after) {
+                limiter.defer(delay);
+            }
+            self.emit(MetricEvent::RateLimited { retry_after });
+        }
 
-        let full_body = Full::new(Bytes::from("{}"));
-        let req = Request::builder()
-            .uri(uri)
-            .method(Method::POST)
-            .header(header::USER_AGENT, USER_AGENT)
-            .header(header::HOST, host)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(full_body)
-            .map_err(|e| WebhookError::Request(e.to_string()))?;
+        Ok(resp)
+    }
+}
+
+impl TryFrom<&str> for Webhook {
+    type Error = WebhookError;
 
-        let resp = sender.send_request(req).await?;
+    fn try_from(url: &str) -> Result<Self> {
+        Self::new(url)
+    }
+}
 
-        let status_code = resp.status().as_u16();
-        let body = resp.into_body();
-        let body_bytes = body.collect().await?.to_bytes();
-        let body_string = String::from_utf8_lossy(&body_bytes).to_string();
+impl TryFrom<String> for Webhook {
+    type Error = WebhookError;
 
-        Ok(WebhookResponse {
-            status_code,
-            body: body_string,
-        })
+    fn try_from(url: String) -> Result<Self> {
+        Self::new(url)
+    }
+}
+
+impl std::str::FromStr for Webhook {
+    type Err = WebhookError;
+
+    fn from_str(url: &str) -> Result<Self> {
+        Self::new(url)
+    }
+}
+
+#[cfg(feature = "gzip")]
+fn decode_gzip(resp: Response<Bytes>) -> Result<Response<Bytes>> {
+    use std::io::Read;
+
+    let gzipped = resp
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("gzip"));
+    if !gzipped {
+        return Ok(resp);
+    }
+
+    let (mut parts, body) = resp.into_parts();
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(body.as_ref()).read_to_end(&mut decoded)?;
+    parts.headers.remove(header::CONTENT_ENCODING);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Ok(Response::from_parts(parts, Bytes::from(decoded)))
+}
+
+fn collect_headers(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut collected: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        collected
+            .entry(name.as_str().to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    collected
+}
+
+fn is_discord_webhook_url(url: &str) -> bool {
+    let Ok(uri) = url.parse::<Uri>() else {
+        return false;
+    };
+    uri.host().is_some_and(is_discord_host) && uri.path().starts_with("/api/webhooks/")
+}
+
+fn is_discord_host(host: &str) -> bool {
+    ["discord.com", "discordapp.com"]
+        .iter()
+        .any(|domain| host == *domain || host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.')))
+}
+
+fn webhook_parts(url: &str) -> Option<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    const MARKER: &str = "/api/webhooks/";
+    let id_start = url.find(MARKER)? + MARKER.len();
+    let id_end = id_start + url[id_start..].find('/')?;
+    let token_start = id_end + 1;
+    let token_len = url[token_start..]
+        .find(['/', '?', '#'])
+        .unwrap_or(url.len() - token_start);
+    Some((id_start..id_end, token_start..token_start + token_len))
+}
+
+fn resolve_redirect(current: &Uri, location: &str) -> Result<Uri> {
+    let invalid = || WebhookError::InvalidResponse(format!("invalid redirect location {:?}", location));
+    let target: Uri = location.parse().map_err(|_| invalid())?;
+    let target = match target.scheme() {
+        Some(_) => target,
+        None if location.starts_with('/') => {
+            let mut parts = current.clone().into_parts();
+            parts.path_and_query = target.path_and_query().cloned();
+            Uri::from_parts(parts).map_err(|_| invalid())?
+        }
+        None => return Err(invalid()),
+    };
+    if target.scheme_str() != Some("https") || !target.host().is_some_and(is_discord_host) {
+        return Err(WebhookError::InvalidResponse(format!(
+            "refusing to follow redirect to {}://{}",
+            target.scheme_str().unwrap_or_default(),
+            target.host().unwrap_or_default()
+        )));
+    }
+    Ok(target)
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn append_query(url: &mut String, key: &str, value: &str) {
+    let separator = match url.find('?') {
+        Some(idx) if idx + 1 == url.len() || url.ends_with('&') => "",
+        Some(_) => "&",
+        None => "?",
+    };
+    url.push_str(separator);
+    url.push_str(key);
+    url.push('=');
+    url.push_str(value);
+}
+
+
+fn parse_retry_after(status: StatusCode, headers: &HeaderMap, body: &str) -> Option<Duration> {
+    if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE {
+        return None;
     }
+    let seconds = headers
+        .get(header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .or_else(|| {
+            serde_json::from_str::<serde_json::Value>(body)
+                .ok()?
+                .get("retry_after")?
+                .as_f64()
+        })?;
+    Duration::try_from_secs_f64(seconds).ok()
 }